@@ -0,0 +1,52 @@
+use crate::error::Error;
+use crate::molecules::load_vote_result;
+use alloc::vec::Vec;
+use ckb_std::ckb_constants::Source;
+use ckb_std::high_level::{QueryIter, load_cell_lock_hash, load_cell_type_hash, load_script};
+
+// result type script hash + candidate index + quorum + owner lock hash
+const ARGS_LEN: usize = 73;
+
+pub(crate) fn entry() -> Result<(), Error> {
+    let args: Vec<u8> = load_script()?.args().raw_data().into();
+    if args.len() != ARGS_LEN {
+        return Err(Error::WrongArgs);
+    }
+    let result_type_hash: [u8; 32] = args[0..32].try_into().unwrap();
+    let candidate = args[32];
+    let quorum = u64::from_le_bytes(args[33..41].try_into().unwrap());
+    let owner_lock_hash: [u8; 32] = args[41..73].try_into().unwrap();
+    if candidate >= 32 {
+        return Err(Error::WrongArgs);
+    }
+
+    // step 1
+    if !QueryIter::new(load_cell_lock_hash, Source::Input).any(|lock| lock == owner_lock_hash) {
+        return Err(Error::NoOwnerLock);
+    }
+    // step 2
+    // The result type script only lets the finalization of the session's
+    // tally cell create a cell with this type script hash.
+    let position = QueryIter::new(load_cell_type_hash, Source::CellDep)
+        .position(|hash| hash == Some(result_type_hash))
+        .ok_or(Error::NoResultCell)?;
+    let result = load_vote_result(position, Source::CellDep)?;
+    // step 3
+    if result.winners()? != 1 << candidate {
+        #[cfg(feature = "enable_log")]
+        log::info!("candidate {} is not the only winner", candidate);
+        return Err(Error::CandidateNotWinning);
+    }
+    // step 4
+    if result.turnout()? < quorum {
+        return Err(Error::QuorumNotReached);
+    }
+    // step 5
+    // `passed` implies `quorum_reached` and the session's pass threshold
+    if result.passed()? != 1 {
+        #[cfg(feature = "enable_log")]
+        log::info!("proposal didn't pass");
+        return Err(Error::ProposalNotPassed);
+    }
+    Ok(())
+}
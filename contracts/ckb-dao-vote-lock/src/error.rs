@@ -0,0 +1,57 @@
+use ckb_std::error::SysError;
+use core::fmt::Display;
+use molecule::lazy_reader::Error as MoleculeError;
+
+#[derive(Debug)]
+pub enum Error {
+    Syscall(SysError),
+    Molecule,
+    WrongArgs,
+    NoOwnerLock,
+    NoResultCell,
+    CandidateNotWinning,
+    QuorumNotReached,
+    ProposalNotPassed,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl From<SysError> for Error {
+    fn from(e: SysError) -> Self {
+        Error::Syscall(e)
+    }
+}
+
+impl From<MoleculeError> for Error {
+    fn from(_: MoleculeError) -> Self {
+        Error::Molecule
+    }
+}
+
+impl Error {
+    pub fn error_code(&self) -> i8 {
+        match self {
+            Error::Syscall(e) => match e {
+                SysError::IndexOutOfBound => 21,
+                SysError::ItemMissing => 22,
+                SysError::LengthNotEnough(_) => 23,
+                SysError::Encoding => 24,
+                SysError::WaitFailure => 25,
+                _ => 26,
+            },
+            Error::Molecule => 51,
+            Error::WrongArgs => 52,
+            Error::NoOwnerLock => 53,
+            Error::NoResultCell => 54,
+            Error::CandidateNotWinning => 55,
+            Error::QuorumNotReached => 56,
+            Error::ProposalNotPassed => 57,
+        }
+    }
+}
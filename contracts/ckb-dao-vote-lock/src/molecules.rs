@@ -0,0 +1,76 @@
+use crate::error::Error;
+use alloc::boxed::Box;
+use ckb_std::{ckb_constants::Source, error::SysError, syscalls};
+
+pub use ckb_dao_vote_types::molecules::vote::*;
+pub use molecule::lazy_reader::{Cursor, Error as MoleculeError, Read};
+
+fn read_data<F: Fn(&mut [u8], usize) -> Result<usize, SysError>>(
+    load_func: F,
+    buf: &mut [u8],
+    offset: usize,
+    total_size: usize,
+) -> Result<usize, MoleculeError> {
+    if offset >= total_size {
+        return Err(MoleculeError::OutOfBound(offset, total_size));
+    }
+    match load_func(buf, offset) {
+        Ok(l) => Ok(l),
+        Err(err) => match err {
+            SysError::LengthNotEnough(_) => Ok(buf.len()),
+            _ => Err(MoleculeError::OutOfBound(offset, total_size)),
+        },
+    }
+}
+
+fn read_size<F: Fn(&mut [u8]) -> Result<usize, SysError>>(load_func: F) -> Result<usize, SysError> {
+    let mut buf = [0u8; 4];
+    match load_func(&mut buf) {
+        Ok(l) => Ok(l),
+        Err(SysError::LengthNotEnough(l)) => Ok(l),
+        Err(e) => Err(e),
+    }
+}
+
+struct DataReader {
+    total_size: usize,
+    index: usize,
+    source: Source,
+}
+
+impl DataReader {
+    fn new(index: usize, source: Source) -> Result<Self, SysError> {
+        let total_size = read_size(|buf| syscalls::load_cell_data(buf, 0, index, source))?;
+        Ok(Self {
+            total_size,
+            source,
+            index,
+        })
+    }
+}
+
+impl Read for DataReader {
+    fn read(&self, buf: &mut [u8], offset: usize) -> Result<usize, MoleculeError> {
+        read_data(
+            |buf, offset| syscalls::load_cell_data(buf, offset, self.index, self.source),
+            buf,
+            offset,
+            self.total_size,
+        )
+    }
+}
+
+impl From<DataReader> for Cursor {
+    fn from(data: DataReader) -> Self {
+        Cursor::new(data.total_size, Box::new(data))
+    }
+}
+
+pub fn load_vote_result(index: usize, source: Source) -> Result<VoteResult, Error> {
+    let reader = DataReader::new(index, source)?;
+    let cursor: Cursor = reader.into();
+    let data = VoteResult::from(cursor);
+    data.verify(false)?;
+
+    Ok(data)
+}
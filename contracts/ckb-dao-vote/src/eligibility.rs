@@ -0,0 +1,77 @@
+use crate::error::{Error, ReadField};
+use crate::molecules::{VoteMeta, load_tx};
+use ckb_dao_vote_types::constants::{DAO_TYPE_HASH, ELIGIBILITY_DEPOSIT_SNAPSHOT, ELIGIBILITY_SMT};
+use ckb_std::ckb_constants::Source;
+use ckb_std::ckb_types::prelude::Unpack;
+use ckb_std::high_level::{
+    QueryIter, load_cell_data, load_cell_lock_hash, load_cell_type_hash, load_header,
+};
+
+pub(crate) struct Snapshot {
+    number: u64,
+    deposit_type_hash: [u8; 32],
+}
+
+fn header_dep_number(block_hash: &[u8; 32]) -> Result<u64, Error> {
+    let header_deps = load_tx()?
+        .raw()
+        .tx("raw")?
+        .header_deps()
+        .tx("header_deps")?;
+    for index in 0..header_deps.len().tx("header_deps")? {
+        if header_deps.get(index).tx("header_deps")? == *block_hash {
+            let header = load_header(index, Source::HeaderDep)?;
+            return Ok(Unpack::<u64>::unpack(&header.raw().number()));
+        }
+    }
+    #[cfg(feature = "enable_log")]
+    log::info!("snapshot header is not in header_deps");
+    Err(Error::WrongEligibility)
+}
+
+pub(crate) fn load_snapshot(vote_meta: &VoteMeta) -> Result<Option<Snapshot>, Error> {
+    match vote_meta
+        .eligibility_mode_opt()
+        .meta("eligibility_mode")?
+        .unwrap_or(ELIGIBILITY_SMT)
+    {
+        ELIGIBILITY_SMT => Ok(None),
+        ELIGIBILITY_DEPOSIT_SNAPSHOT => {
+            let block_hash = vote_meta
+                .snapshot_block_hash_opt()
+                .meta("snapshot_block_hash")?
+                .ok_or(Error::WrongEligibility)?;
+            Ok(Some(Snapshot {
+                number: header_dep_number(&block_hash)?,
+                deposit_type_hash: vote_meta
+                    .deposit_type_hash_opt()
+                    .meta("deposit_type_hash")?
+                    .unwrap_or(DAO_TYPE_HASH),
+            }))
+        }
+        _ => Err(Error::WrongEligibility),
+    }
+}
+
+// A deposit of the voter in cell_deps, created at or before the snapshot. Cell
+// deps are live cells, so the deposit is still unspent.
+pub(crate) fn verify_deposit(snapshot: &Snapshot, lock_hash: &[u8; 32]) -> Result<(), Error> {
+    for (index, type_hash) in QueryIter::new(load_cell_type_hash, Source::CellDep).enumerate() {
+        if type_hash != Some(snapshot.deposit_type_hash)
+            || load_cell_lock_hash(index, Source::CellDep)? != *lock_hash
+            || load_cell_data(index, Source::CellDep)?[..] != [0u8; 8]
+        {
+            continue;
+        }
+        // the header of the deposit block must be in header_deps
+        let Ok(header) = load_header(index, Source::CellDep) else {
+            continue;
+        };
+        if Unpack::<u64>::unpack(&header.raw().number()) <= snapshot.number {
+            return Ok(());
+        }
+    }
+    #[cfg(feature = "enable_log")]
+    log::info!("no deposit at or before the snapshot");
+    Err(Error::WrongEligibility)
+}
@@ -1,24 +1,63 @@
-use crate::error::Error;
-use crate::molecules::{load_tx, load_vote_meta, load_vote_proof};
+use crate::eligibility::{Snapshot, load_snapshot, verify_deposit};
+use crate::error::{Error, ReadField};
+use crate::meta::{self, requires_meta_type};
+use crate::molecules::{VoteMeta, load_tx, load_vote_meta, load_vote_proof};
+use crate::tally::{self, ballot_weight, has_result_cell_dep, verify_tally_update};
 use alloc::vec::Vec;
-use ckb_hash::new_blake2b;
+use ckb_dao_vote_types::constants::{
+    BITMAP_LEN, GUARDED_ARGS_LEN, META_ARGS, RESULT_ARGS_LEN, SMT_VALUE, TALLY_ARGS_LEN,
+    VOTE_META_V1, VOTE_META_V2, WEIGHT_MODE_BALLOT, WEIGHT_MODE_ESCROW,
+};
+use ckb_dao_vote_types::hash::blake160;
 use ckb_std::ckb_constants::Source;
-use ckb_std::ckb_types::packed::{Byte, Byte32};
+use ckb_std::ckb_types::packed::{Byte, Byte32, Header};
+use ckb_std::ckb_types::prelude::{Entity, Unpack};
 use ckb_std::high_level::{
-    QueryIter, load_cell_data, load_cell_lock_hash, load_cell_type, load_script,
+    QueryIter, load_cell_capacity, load_cell_data, load_cell_lock_hash, load_cell_type,
+    load_header, load_input_out_point, load_input_since, load_script,
 };
+use ckb_std::since::{EpochNumberWithFraction, LockValue, Since};
 use sparse_merkle_tree::SMTBuilder;
 
-const SMT_VALUE: [u8; 32] = [
-    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-];
+pub(crate) fn vote_meta_version(vote_meta: &VoteMeta) -> Result<u8, Error> {
+    Ok(vote_meta
+        .version_opt()
+        .meta("version")?
+        .unwrap_or(VOTE_META_V1))
+}
 
-fn blake160(bytes: &[u8]) -> [u8; 20] {
-    let mut blake2b = new_blake2b();
-    blake2b.update(bytes);
-    let mut ret = [0; 32];
-    blake2b.finalize(&mut ret);
-    ret[0..20].try_into().unwrap()
+// Candidate records describe `candidates` in the same order: one record per
+// candidate, with a UTF-8 title and an id unique within the session.
+fn verify_candidate_records(vote_meta: &VoteMeta, candidates_size: usize) -> Result<(), Error> {
+    let Some(records) = vote_meta
+        .candidate_records_opt()
+        .meta("candidate_records")?
+    else {
+        return Ok(());
+    };
+    if records.len().meta("candidate_records")? != candidates_size {
+        return Err(Error::WrongCandidateRecord);
+    }
+    let mut ids = Vec::with_capacity(candidates_size);
+    for index in 0..candidates_size {
+        let record = records.get(index).meta("candidate_records")?;
+        let title: Vec<u8> = record
+            .title()
+            .meta("candidate_records")?
+            .try_into()
+            .meta("candidate_records")?;
+        if core::str::from_utf8(&title).is_err() {
+            return Err(Error::WrongCandidateRecord);
+        }
+        ids.push(record.id().meta("candidate_records")?);
+    }
+    ids.sort_unstable();
+    if ids.windows(2).any(|pair| pair[0] == pair[1]) {
+        #[cfg(feature = "enable_log")]
+        log::info!("duplicate candidate id");
+        return Err(Error::WrongCandidateRecord);
+    }
+    Ok(())
 }
 
 fn count_matching_type_scripts(
@@ -29,13 +68,141 @@ fn count_matching_type_scripts(
     QueryIter::new(load_cell_type, source)
         .flatten()
         .filter(|script| {
-            script.code_hash() == *target_code_hash && script.hash_type() == target_hash_type
+            script.code_hash() == *target_code_hash
+                && script.hash_type() == target_hash_type
+                && !is_special_args(&script.args().raw_data())
         })
         .count()
 }
 
+// tally, result and vote meta cells share the code of vote cells
+fn is_special_args(args: &[u8]) -> bool {
+    matches!(args.len(), TALLY_ARGS_LEN | RESULT_ARGS_LEN) || args == META_ARGS
+}
+
+pub(crate) fn is_vote_args(args: &[u8], session_id: &[u8; 20]) -> bool {
+    (args.len() == 20 || args.len() == GUARDED_ARGS_LEN) && args[0..20] == session_id[..]
+}
+
+// There is no direct syscall to fetch cell_deps, so we need to fetch it from the transaction indirectly.
+pub(crate) fn find_meta_cell_dep(session_id: &[u8; 20]) -> Result<Option<usize>, Error> {
+    let tx = load_tx()?;
+    let cell_deps = tx.raw().tx("raw")?.cell_deps().tx("cell_deps")?;
+    let position = cell_deps.into_iter().enumerate().find_map(|(index, dep)| {
+        let out_point = dep.out_point().ok()?;
+        let bytes: Vec<u8> = out_point.cursor.try_into().ok()?;
+        if blake160(&bytes) == *session_id {
+            Some(index)
+        } else {
+            None
+        }
+    });
+    Ok(position)
+}
+
+fn epoch_reached(current: &EpochNumberWithFraction, end: &EpochNumberWithFraction) -> bool {
+    current.number() > end.number()
+        || (current.number() == end.number()
+            && current.index() * end.length() >= end.index() * current.length())
+}
+
+// Both values must be absolute and use the same metric, otherwise the end is
+// not considered reached.
+pub(crate) fn since_reached(current: &Since, end: &Since) -> bool {
+    if !current.is_absolute() || !end.is_absolute() {
+        return false;
+    }
+    match (current.extract_lock_value(), end.extract_lock_value()) {
+        (Some(LockValue::BlockNumber(current)), Some(LockValue::BlockNumber(end))) => {
+            current >= end
+        }
+        (Some(LockValue::Timestamp(current)), Some(LockValue::Timestamp(end))) => current >= end,
+        (
+            Some(LockValue::EpochNumberWithFraction(current)),
+            Some(LockValue::EpochNumberWithFraction(end)),
+        ) => epoch_reached(&current, &end),
+        _ => false,
+    }
+}
+
+// An absolute since with a known metric and a well formed epoch, i.e. one that
+// `since_reached` can ever consider reached.
+pub(crate) fn is_absolute_since(since: &Since) -> bool {
+    if !since.is_absolute() || !since.flags_is_valid() {
+        return false;
+    }
+    match since.extract_lock_value() {
+        Some(LockValue::EpochNumberWithFraction(epoch)) => epoch.index() < epoch.length(),
+        Some(_) => true,
+        None => false,
+    }
+}
+
+// Express the position of a header in the same metric as `end`.
+fn header_since(header: &Header, end: &Since) -> Option<Since> {
+    let raw = header.raw();
+    match end.extract_lock_value()? {
+        LockValue::BlockNumber(_) => Since::from_block_number(raw.number().unpack(), true),
+        LockValue::EpochNumberWithFraction(_) => Some(Since::from_epoch(
+            EpochNumberWithFraction::from_full_value(raw.epoch().unpack()),
+            true,
+        )),
+        // header timestamp is in milliseconds, since timestamp in seconds
+        LockValue::Timestamp(_) => {
+            Since::from_timestamp(Unpack::<u64>::unpack(&raw.timestamp()) / 1000, true)
+        }
+    }
+}
+
+// A header dep at or after the session end
+pub(crate) fn header_dep_reached(end_time: &Since) -> bool {
+    QueryIter::new(load_header, Source::HeaderDep).any(|header| {
+        header_since(&header, end_time).is_some_and(|since| since_reached(&since, end_time))
+    })
+}
+
+fn verify_consumption(args: &[u8]) -> Result<(), Error> {
+    if args.len() != GUARDED_ARGS_LEN {
+        return Ok(());
+    }
+    let session_id: [u8; 20] = args[0..20].try_into().unwrap();
+    let end_time = Since::new(u64::from_le_bytes(args[20..].try_into().unwrap()));
+
+    // The vote meta cell is consumed in the same transaction: session closed.
+    if QueryIter::new(load_input_out_point, Source::Input)
+        .any(|out_point| blake160(out_point.as_slice()) == session_id)
+    {
+        return Ok(());
+    }
+    // A result cell of the session: it was finalized, the meta cell is consumed
+    if has_result_cell_dep(&session_id)? {
+        return Ok(());
+    }
+    if header_dep_reached(&end_time) {
+        return Ok(());
+    }
+    // Every consumed vote cell is time-locked until the session end
+    if QueryIter::new(load_input_since, Source::GroupInput)
+        .all(|since| since_reached(&Since::new(since), &end_time))
+    {
+        return Ok(());
+    }
+    #[cfg(feature = "enable_log")]
+    log::info!("vote session is still live");
+    Err(Error::EarlyConsumption)
+}
+
 pub(crate) fn entry() -> Result<(), Error> {
     let current_script = load_script()?;
+    let args = current_script.args().raw_data();
+    if args[..] == META_ARGS {
+        return meta::entry();
+    }
+    match args.len() {
+        TALLY_ARGS_LEN => return tally::entry(),
+        RESULT_ARGS_LEN => return tally::result_entry(),
+        _ => {}
+    }
     let current_code_hash = current_script.code_hash();
     let current_hash_type = current_script.hash_type();
 
@@ -47,89 +214,262 @@ pub(crate) fn entry() -> Result<(), Error> {
     if input_count > 0 && output_count > 0 {
         return Err(Error::WrongTxType);
     }
+    let args: Vec<u8> = current_script.args().raw_data().into();
     // step 2
     if input_count > 0 {
         // vote consumption
-        return Ok(());
+        return verify_consumption(&args);
     }
     // vote creation
-    let args: Vec<u8> = current_script.args().raw_data().into();
-    if args.len() != 20 {
+    if args.len() != 20 && args.len() != GUARDED_ARGS_LEN {
         return Err(Error::WrongArgs);
     }
-    let args: [u8; 20] = args.try_into().unwrap();
-
-    // There is no direct syscall to fetch cell_deps, so we need to fetch it from the transaction indirectly.
-    let tx = load_tx()?;
-    let cell_deps = tx.raw()?.cell_deps()?;
-    let position = cell_deps.into_iter().enumerate().find_map(|(index, dep)| {
-        let out_point = dep.out_point().ok()?;
-        let bytes: Vec<u8> = out_point.cursor.try_into().ok()?;
-        if blake160(&bytes) == args {
-            Some(index)
-        } else {
-            None
-        }
-    });
+    let session_id: [u8; 20] = args[0..20].try_into().unwrap();
+    let position = find_meta_cell_dep(&session_id)?;
 
     // step 3
     if position.is_none() {
         return Err(Error::NoMetaCell);
     }
     let position = position.unwrap();
-    let vote_meta = load_vote_meta(position)?;
-    let root_hash = vote_meta.smt_root_hash()?;
-
-    let iter = QueryIter::new(load_cell_type, Source::GroupOutput);
-    for (index, _) in iter.enumerate() {
-        let vote_proof = load_vote_proof(index)?;
-        let hash: [u8; 32] = vote_proof.lock_script_hash()?;
-        // Only users included in the SMT can vote (restricted vote)
-        if root_hash.is_some() {
-            let root_hash: [u8; 32] = root_hash.unwrap();
-
-            let proof = vote_proof.smt_proof()?;
-            let proof: Vec<u8> = proof.try_into()?;
-            let smt_builder = SMTBuilder::new();
-            let smt_builder = smt_builder
-                .insert(&hash.into(), &SMT_VALUE.into())
-                .map_err(|_| Error::VerifySmtFail)?;
-            let smt = smt_builder.build().map_err(|_| Error::VerifySmtFail)?;
-            // step 4
-            if smt
-                .verify(&root_hash.into(), &proof)
-                .map_err(|_| Error::VerifySmtFail)
-                .is_err()
-            {
-                #[cfg(feature = "enable_log")]
-                log::info!("SMT verify failed. Not on tree.");
-                return Err(Error::VerifySmtFail);
-            }
+    let vote_meta = load_vote_meta(position, Source::CellDep)?;
+    // A bond or closure policy is only enforced by the vote meta type script.
+    if requires_meta_type(&vote_meta)?
+        && !load_cell_type(position, Source::CellDep)?.is_some_and(|script| {
+            script.code_hash() == current_code_hash
+                && script.hash_type() == current_hash_type
+                && script.args().raw_data()[..] == META_ARGS
+        })
+    {
+        return Err(Error::WrongBond);
+    }
+    match vote_meta_version(&vote_meta)? {
+        version @ (VOTE_META_V1 | VOTE_META_V2) => {
+            verify_creation(&vote_meta, version, &args, &session_id)?
         }
-        // step 5
-        if !QueryIter::new(load_cell_lock_hash, Source::Input).any(|lock| lock == hash) {
-            return Err(Error::NoLockFound);
+        _ => {
+            #[cfg(feature = "enable_log")]
+            log::info!("unsupported vote meta version");
+            return Err(Error::UnsupportedVersion);
         }
+    }
+    #[cfg(feature = "enable_log")]
+    log::info!("ckb-dao-vote, exit successfully");
+    Ok(())
+}
 
-        // step 6
-        let cell_data = load_cell_data(index, Source::GroupOutput)?;
-        if cell_data.len() != 4 {
-            return Err(Error::WrongVoteCandidate);
-        }
-        let choices = u32::from_le_bytes(cell_data.try_into().unwrap());
-        if choices == 0 {
-            return Err(Error::WrongVoteCandidate);
+// Version 1 ballots are a 4-byte bitmap. Version 2 ballots append the ballot
+// weight as a little-endian u64.
+fn verify_creation(
+    vote_meta: &VoteMeta,
+    version: u8,
+    args: &[u8],
+    session_id: &[u8; 20],
+) -> Result<(), Error> {
+    let root_hash = vote_meta.smt_root_hash().meta("smt_root_hash")?;
+    let snapshot = load_snapshot(vote_meta)?;
+    // Guarded sessions must carry the session end in args, so that consumption
+    // can be checked without the vote meta cell.
+    if vote_meta
+        .guarded_consumption_opt()
+        .meta("guarded_consumption")?
+        .unwrap_or(0)
+        != 0
+    {
+        let end_time = vote_meta.end_time().meta("end_time")?;
+        // an end that is never reached would lock the vote cells forever
+        if !is_absolute_since(&Since::new(end_time)) {
+            return Err(Error::WrongArgs);
         }
-        let candidates_size = vote_meta.candidates()?.len()?;
-        if candidates_size > 32 {
-            return Err(Error::WrongVoteCandidate);
+        if args.len() != GUARDED_ARGS_LEN || args[20..] != end_time.to_le_bytes() {
+            return Err(Error::WrongArgs);
         }
-        let highest_bit = 32 - choices.leading_zeros() as usize;
-        if highest_bit > candidates_size {
-            return Err(Error::WrongVoteCandidate);
+    } else if args.len() != 20 {
+        return Err(Error::WrongArgs);
+    }
+    let (min_vote_capacity, max_vote_capacity) = vote_capacity_range(vote_meta)?;
+    let tally_type_hash = vote_meta.tally_type_hash_opt().meta("tally_type_hash")?;
+    let weight_mode = vote_meta
+        .weight_mode_opt()
+        .meta("weight_mode")?
+        .unwrap_or(WEIGHT_MODE_BALLOT);
+    // an escrow weight is only meaningful when recorded in the ballot
+    if weight_mode == WEIGHT_MODE_ESCROW && version == VOTE_META_V1 {
+        return Err(Error::WrongWeightMode);
+    }
+    let ballot_len = match version {
+        VOTE_META_V1 => BITMAP_LEN,
+        _ => BITMAP_LEN + 8,
+    };
+    let allow_abstain = vote_meta
+        .allow_abstain_opt()
+        .meta("allow_abstain")?
+        .unwrap_or(0)
+        != 0;
+    let candidates_size = vote_meta
+        .candidates()
+        .meta("candidates")?
+        .len()
+        .meta("candidates")?;
+    verify_candidate_records(vote_meta, candidates_size)?;
+    let rules = BallotRules {
+        vote_meta,
+        version,
+        root_hash,
+        snapshot,
+        ballot_len,
+        allow_abstain,
+        candidates_size,
+        min_vote_capacity,
+        max_vote_capacity,
+        weight_mode,
+    };
+    let mut ballots = Vec::new();
+
+    let iter = QueryIter::new(load_cell_type, Source::GroupOutput);
+    for (index, _) in iter.enumerate() {
+        let ballot = verify_ballot(&rules, index);
+        #[cfg(feature = "enable_log")]
+        if let Err(err) = &ballot {
+            log::debug!("output={} error={:?} code={}", index, err, err.error_code());
         }
+        ballots.push(ballot?);
+    }
+    if let Some(tally_type_hash) = tally_type_hash {
+        verify_tally_update(&tally_type_hash, session_id, candidates_size, &ballots)?;
     }
-    #[cfg(feature = "enable_log")]
-    log::info!("ckb-dao-vote, exit successfully");
     Ok(())
 }
+
+struct BallotRules<'a> {
+    vote_meta: &'a VoteMeta,
+    version: u8,
+    root_hash: Option<[u8; 32]>,
+    snapshot: Option<Snapshot>,
+    ballot_len: usize,
+    allow_abstain: bool,
+    candidates_size: usize,
+    min_vote_capacity: Option<u64>,
+    max_vote_capacity: Option<u64>,
+    weight_mode: u8,
+}
+
+// Verifies the vote cell at `index` of the script group and returns the
+// voter's lock script hash, the choices and the weight.
+fn verify_ballot(rules: &BallotRules, index: usize) -> Result<([u8; 32], u32, u64), Error> {
+    let vote_proof = load_vote_proof(index)?;
+    let hash: [u8; 32] = vote_proof.lock_script_hash().witness("lock_script_hash")?;
+    // Only users included in the SMT can vote (restricted vote)
+    if let Some(root_hash) = rules.root_hash {
+        let proof = vote_proof.smt_proof().witness("smt_proof")?;
+        let proof: Vec<u8> = proof.try_into().witness("smt_proof")?;
+        let smt_builder = SMTBuilder::new();
+        let smt_builder = smt_builder
+            .insert(&hash.into(), &SMT_VALUE.into())
+            .map_err(|_| Error::VerifySmtFail)?;
+        let smt = smt_builder.build().map_err(|_| Error::VerifySmtFail)?;
+        // step 4
+        if smt
+            .verify(&root_hash.into(), &proof)
+            .map_err(|_| Error::VerifySmtFail)
+            .is_err()
+        {
+            #[cfg(feature = "enable_log")]
+            log::info!("SMT verify failed. Not on tree.");
+            return Err(Error::VerifySmtFail);
+        }
+    }
+    if let Some(snapshot) = &rules.snapshot {
+        verify_deposit(snapshot, &hash)?;
+    }
+    // step 5
+    if !QueryIter::new(load_cell_lock_hash, Source::Input).any(|lock| lock == hash) {
+        return Err(Error::NoLockFound);
+    }
+
+    // step 6
+    let cell_data = load_cell_data(index, Source::GroupOutput)?;
+    if cell_data.len() != rules.ballot_len {
+        #[cfg(feature = "enable_log")]
+        log::debug!(
+            "output={} ballot_len={} expected={}",
+            index,
+            cell_data.len(),
+            rules.ballot_len
+        );
+        return Err(Error::WrongBallotLength);
+    }
+    let choices = u32::from_le_bytes(cell_data[..BITMAP_LEN].try_into().unwrap());
+    // an empty bitmap is an abstain ballot
+    if choices == 0 && !rules.allow_abstain {
+        #[cfg(feature = "enable_log")]
+        log::debug!("output={} choices=0 allow_abstain=false", index);
+        return Err(Error::AbstainNotAllowed);
+    }
+    if rules.candidates_size > 32 {
+        #[cfg(feature = "enable_log")]
+        log::debug!(
+            "output={} candidates={} max=32",
+            index,
+            rules.candidates_size
+        );
+        return Err(Error::TooManyCandidates);
+    }
+    let highest_bit = 32 - choices.leading_zeros() as usize;
+    if highest_bit > rules.candidates_size {
+        #[cfg(feature = "enable_log")]
+        log::debug!(
+            "output={} choices={:#b} candidates={}",
+            index,
+            choices,
+            rules.candidates_size
+        );
+        return Err(Error::WrongVoteCandidate);
+    }
+    let capacity = load_cell_capacity(index, Source::GroupOutput)?;
+    if rules.min_vote_capacity.is_some_and(|min| capacity < min)
+        || rules.max_vote_capacity.is_some_and(|max| capacity > max)
+    {
+        #[cfg(feature = "enable_log")]
+        log::debug!(
+            "output={} capacity={} min={:?} max={:?}",
+            index,
+            capacity,
+            rules.min_vote_capacity,
+            rules.max_vote_capacity
+        );
+        return Err(Error::WrongVoteCapacity);
+    }
+    let weight = ballot_weight(rules.vote_meta, rules.weight_mode, index)?;
+    if rules.version != VOTE_META_V1 && cell_data[BITMAP_LEN..] != weight.to_le_bytes() {
+        #[cfg(feature = "enable_log")]
+        log::debug!(
+            "output={} recorded_weight={:?} expected={}",
+            index,
+            &cell_data[BITMAP_LEN..],
+            weight
+        );
+        return Err(Error::WrongBallotWeight);
+    }
+    Ok((hash, choices, weight))
+}
+
+// An inverted range would silently reject every ballot of the session.
+pub(crate) fn vote_capacity_range(
+    vote_meta: &VoteMeta,
+) -> Result<(Option<u64>, Option<u64>), Error> {
+    let min = vote_meta
+        .min_vote_capacity_opt()
+        .meta("min_vote_capacity")?;
+    let max = vote_meta
+        .max_vote_capacity_opt()
+        .meta("max_vote_capacity")?;
+    if let (Some(min), Some(max)) = (min, max) {
+        if min > max {
+            #[cfg(feature = "enable_log")]
+            log::info!("min_vote_capacity {} > max_vote_capacity {}", min, max);
+            return Err(Error::WrongVoteCapacity);
+        }
+    }
+    Ok((min, max))
+}
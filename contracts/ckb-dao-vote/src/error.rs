@@ -1,17 +1,58 @@
+use ckb_dao_vote_types::error::ErrorCode;
 use ckb_std::error::SysError;
 use core::fmt::Display;
 use molecule::lazy_reader::Error as MoleculeError;
 
+/// Data read through a lazy reader, see `molecules.rs`.
+#[derive(Clone, Copy, Debug)]
+pub enum DataKind {
+    VoteMeta,
+    Tally,
+    VoteResult,
+    Witness,
+    Tx,
+}
+
+#[derive(Debug)]
+pub enum ReadCause {
+    /// loading the data failed
+    Syscall(SysError),
+    /// the data doesn't match the schema
+    Molecule(MoleculeError),
+    /// a required option is none
+    Absent,
+}
+
 #[derive(Debug)]
 pub enum Error {
     Syscall(SysError),
+    /// lazy reader error not attributed to a field
     Molecule,
+    Read {
+        kind: DataKind,
+        field: &'static str,
+        cause: ReadCause,
+    },
     WrongTxType,
     WrongArgs,
     NoMetaCell,
     VerifySmtFail,
     NoLockFound,
     WrongVoteCandidate,
+    WrongBallotLength,
+    AbstainNotAllowed,
+    TooManyCandidates,
+    WrongVoteCapacity,
+    EarlyConsumption,
+    WrongTally,
+    WrongWeightMode,
+    WrongResult,
+    WrongCandidateRecord,
+    UnsupportedVersion,
+    WrongBond,
+    WrongClosure,
+    WrongEligibility,
+    WrongBallotWeight,
 }
 
 impl Display for Error {
@@ -34,24 +75,114 @@ impl From<MoleculeError> for Error {
     }
 }
 
+/// Attributes a failed read to the data and the field being parsed.
+pub trait ReadField<T> {
+    fn field(self, kind: DataKind, field: &'static str) -> Result<T, Error>;
+
+    fn meta(self, field: &'static str) -> Result<T, Error>
+    where
+        Self: Sized,
+    {
+        self.field(DataKind::VoteMeta, field)
+    }
+
+    fn tally(self, field: &'static str) -> Result<T, Error>
+    where
+        Self: Sized,
+    {
+        self.field(DataKind::Tally, field)
+    }
+
+    fn vote_result(self, field: &'static str) -> Result<T, Error>
+    where
+        Self: Sized,
+    {
+        self.field(DataKind::VoteResult, field)
+    }
+
+    fn witness(self, field: &'static str) -> Result<T, Error>
+    where
+        Self: Sized,
+    {
+        self.field(DataKind::Witness, field)
+    }
+
+    fn tx(self, field: &'static str) -> Result<T, Error>
+    where
+        Self: Sized,
+    {
+        self.field(DataKind::Tx, field)
+    }
+}
+
+impl<T> ReadField<T> for Result<T, MoleculeError> {
+    fn field(self, kind: DataKind, field: &'static str) -> Result<T, Error> {
+        self.map_err(|e| Error::Read {
+            kind,
+            field,
+            cause: ReadCause::Molecule(e),
+        })
+    }
+}
+
+impl<T> ReadField<T> for Result<T, SysError> {
+    fn field(self, kind: DataKind, field: &'static str) -> Result<T, Error> {
+        self.map_err(|e| Error::Read {
+            kind,
+            field,
+            cause: ReadCause::Syscall(e),
+        })
+    }
+}
+
 impl Error {
     pub fn error_code(&self) -> i8 {
-        match self {
+        let code = match self {
             Error::Syscall(e) => match e {
-                SysError::IndexOutOfBound => 21,
-                SysError::ItemMissing => 22,
-                SysError::LengthNotEnough(_) => 23,
-                SysError::Encoding => 24,
-                SysError::WaitFailure => 25,
-                _ => 26,
+                SysError::IndexOutOfBound => ErrorCode::IndexOutOfBound,
+                SysError::ItemMissing => ErrorCode::ItemMissing,
+                SysError::LengthNotEnough(_) => ErrorCode::LengthNotEnough,
+                SysError::Encoding => ErrorCode::Encoding,
+                SysError::WaitFailure => ErrorCode::WaitFailure,
+                _ => ErrorCode::UnknownSyscall,
             },
-            Error::Molecule => 51,
-            Error::WrongTxType => 52,
-            Error::WrongArgs => 53,
-            Error::NoMetaCell => 54,
-            Error::VerifySmtFail => 55,
-            Error::NoLockFound => 56,
-            Error::WrongVoteCandidate => 57,
-        }
+            Error::Molecule => ErrorCode::Molecule,
+            Error::Read { kind, cause, .. } => {
+                let missing = matches!(cause, ReadCause::Syscall(_));
+                match (kind, missing) {
+                    (DataKind::VoteMeta, true) => ErrorCode::MissingMeta,
+                    (DataKind::VoteMeta, false) => ErrorCode::CorruptMeta,
+                    (DataKind::Tally, true) => ErrorCode::MissingTally,
+                    (DataKind::Tally, false) => ErrorCode::CorruptTally,
+                    (DataKind::VoteResult, true) => ErrorCode::MissingResult,
+                    (DataKind::VoteResult, false) => ErrorCode::CorruptResult,
+                    (DataKind::Witness, true) => ErrorCode::MissingWitness,
+                    (DataKind::Witness, false) => ErrorCode::CorruptWitness,
+                    (DataKind::Tx, true) => ErrorCode::MissingTx,
+                    (DataKind::Tx, false) => ErrorCode::CorruptTx,
+                }
+            }
+            Error::WrongTxType => ErrorCode::WrongTxType,
+            Error::WrongArgs => ErrorCode::WrongArgs,
+            Error::NoMetaCell => ErrorCode::NoMetaCell,
+            Error::VerifySmtFail => ErrorCode::VerifySmtFail,
+            Error::NoLockFound => ErrorCode::NoLockFound,
+            Error::WrongVoteCandidate => ErrorCode::WrongVoteCandidate,
+            Error::WrongBallotLength => ErrorCode::WrongBallotLength,
+            Error::AbstainNotAllowed => ErrorCode::AbstainNotAllowed,
+            Error::TooManyCandidates => ErrorCode::TooManyCandidates,
+            Error::WrongVoteCapacity => ErrorCode::WrongVoteCapacity,
+            Error::EarlyConsumption => ErrorCode::EarlyConsumption,
+            Error::WrongTally => ErrorCode::WrongTally,
+            Error::WrongWeightMode => ErrorCode::WrongWeightMode,
+            Error::WrongResult => ErrorCode::WrongResult,
+            Error::WrongCandidateRecord => ErrorCode::WrongCandidateRecord,
+            Error::UnsupportedVersion => ErrorCode::UnsupportedVersion,
+            Error::WrongBond => ErrorCode::WrongBond,
+            Error::WrongClosure => ErrorCode::WrongClosure,
+            Error::WrongEligibility => ErrorCode::WrongEligibility,
+            Error::WrongBallotWeight => ErrorCode::WrongBallotWeight,
+        };
+        code.code()
     }
 }
@@ -0,0 +1,27 @@
+#![cfg_attr(not(any(feature = "library", test)), no_std)]
+// The modules live in the library so that the native simulator, which only
+// links the library, resolves `crate::` paths the same way as the binary.
+extern crate alloc;
+
+mod eligibility;
+mod entry;
+mod error;
+mod meta;
+mod molecules;
+mod tally;
+
+pub fn program_entry() -> i8 {
+    #[cfg(feature = "enable_log")]
+    {
+        drop(ckb_std::logger::init());
+        log::info!("ckb-dao-vote, log enabled");
+    }
+    match entry::entry() {
+        Ok(_) => 0,
+        Err(e) => {
+            #[cfg(feature = "enable_log")]
+            log::error!("error: {:?}", e);
+            e.error_code()
+        }
+    }
+}
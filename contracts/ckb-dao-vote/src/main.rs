@@ -1,12 +1,15 @@
-#![cfg_attr(not(any(test)), no_std)]
+#![cfg_attr(not(any(feature = "library", test)), no_std)]
 #![cfg_attr(not(test), no_main)]
 
-#[cfg(test)]
+#[cfg(any(feature = "library", test))]
 extern crate alloc;
 
-#[cfg(not(any(test)))]
+#[cfg(not(any(feature = "library", test)))]
+use ckb_dao_vote::program_entry;
+
+#[cfg(not(any(feature = "library", test)))]
 ckb_std::entry!(program_entry);
-#[cfg(not(any(test)))]
+#[cfg(not(any(feature = "library", test)))]
 // By default, the following heap configuration is used:
 // * 16KB fixed heap
 // * 1.2MB(rounded up to be 16-byte aligned) dynamic heap
@@ -14,24 +17,3 @@ ckb_std::entry!(program_entry);
 // For more details, please refer to ckb-std's default_alloc macro
 // and the buddy-alloc alloc implementation.
 ckb_std::default_alloc!(16384, 1258306, 64);
-
-mod entry;
-mod error;
-mod molecules;
-mod smt_hasher;
-
-pub fn program_entry() -> i8 {
-    #[cfg(feature = "enable_log")]
-    {
-        drop(ckb_std::logger::init());
-        log::info!("ckb-dao-vote, log enabled");
-    }
-    match entry::entry() {
-        Ok(_) => 0,
-        Err(e) => {
-            #[cfg(feature = "enable_log")]
-            log::error!("error: {:?}", e);
-            e.error_code()
-        }
-    }
-}
@@ -0,0 +1,199 @@
+use crate::entry::{
+    header_dep_reached, is_absolute_since, since_reached, vote_capacity_range, vote_meta_version,
+};
+use crate::error::{Error, ReadField};
+use crate::molecules::{VoteMeta, load_vote_meta};
+use crate::tally::find_result;
+use alloc::vec::Vec;
+use ckb_dao_vote_types::constants::{
+    CLOSE_AFTER_END, CLOSE_BY_COMMITTEE, VOTE_META_V1, VOTE_META_V2,
+};
+use ckb_dao_vote_types::hash::blake160;
+use ckb_std::ckb_constants::Source;
+use ckb_std::ckb_types::prelude::Entity;
+use ckb_std::high_level::{
+    QueryIter, load_cell_capacity, load_cell_lock_hash, load_cell_type, load_input_out_point,
+    load_input_since,
+};
+use ckb_std::since::Since;
+
+// Sessions using any of these fields rely on the vote meta type script.
+pub(crate) fn requires_meta_type(vote_meta: &VoteMeta) -> Result<bool, Error> {
+    Ok(vote_meta
+        .proposer_bond_opt()
+        .meta("proposer_bond")?
+        .is_some()
+        || vote_meta
+            .closure_policy_opt()
+            .meta("closure_policy")?
+            .unwrap_or(0)
+            != 0)
+}
+
+fn verify_policy(vote_meta: &VoteMeta) -> Result<(), Error> {
+    let policy = vote_meta
+        .closure_policy_opt()
+        .meta("closure_policy")?
+        .unwrap_or(0);
+    if policy & !(CLOSE_AFTER_END | CLOSE_BY_COMMITTEE) != 0 {
+        return Err(Error::WrongClosure);
+    }
+    if policy & CLOSE_BY_COMMITTEE == 0 {
+        return Ok(());
+    }
+    let committee = vote_meta
+        .committee_opt()
+        .meta("committee")?
+        .ok_or(Error::WrongClosure)?;
+    let threshold = vote_meta
+        .committee_threshold_opt()
+        .meta("committee_threshold")?
+        .unwrap_or(0) as usize;
+    let mut members = Vec::new();
+    for index in 0..committee.len().meta("committee")? {
+        members.push(committee.get(index).meta("committee")?);
+    }
+    members.sort_unstable();
+    members.dedup();
+    if threshold == 0
+        || threshold > members.len()
+        || members.len() != committee.len().meta("committee")?
+    {
+        return Err(Error::WrongClosure);
+    }
+    Ok(())
+}
+
+// At least `committee_threshold` committee members have a lock in the inputs.
+fn verify_committee(vote_meta: &VoteMeta) -> Result<(), Error> {
+    let committee = vote_meta
+        .committee_opt()
+        .meta("committee")?
+        .ok_or(Error::WrongClosure)?;
+    let threshold = vote_meta
+        .committee_threshold_opt()
+        .meta("committee_threshold")?
+        .unwrap_or(0) as usize;
+    let mut present = 0;
+    for index in 0..committee.len().meta("committee")? {
+        let member = committee.get(index).meta("committee")?;
+        if QueryIter::new(load_cell_lock_hash, Source::Input).any(|lock| lock == member) {
+            present += 1;
+        }
+    }
+    if threshold == 0 || present < threshold {
+        #[cfg(feature = "enable_log")]
+        log::info!("{} of {} committee members present", present, threshold);
+        return Err(Error::WrongClosure);
+    }
+    Ok(())
+}
+
+fn verify_opening(index: usize) -> Result<(), Error> {
+    let vote_meta = load_vote_meta(index, Source::GroupOutput)?;
+    if !matches!(vote_meta_version(&vote_meta)?, VOTE_META_V1 | VOTE_META_V2) {
+        return Err(Error::UnsupportedVersion);
+    }
+    vote_capacity_range(&vote_meta)?;
+    verify_policy(&vote_meta)?;
+    // the vote cells of a guarded session carry `end_time` in their args
+    if vote_meta
+        .guarded_consumption_opt()
+        .meta("guarded_consumption")?
+        .unwrap_or(0)
+        != 0
+        && !is_absolute_since(&Since::new(vote_meta.end_time().meta("end_time")?))
+    {
+        #[cfg(feature = "enable_log")]
+        log::info!("end_time of a guarded session must be an absolute since");
+        return Err(Error::WrongArgs);
+    }
+    if let Some(bond) = vote_meta.proposer_bond_opt().meta("proposer_bond")? {
+        if load_cell_capacity(index, Source::GroupOutput)? < bond {
+            #[cfg(feature = "enable_log")]
+            log::info!("vote meta cell holds less than the proposer bond");
+            return Err(Error::WrongBond);
+        }
+    }
+    Ok(())
+}
+
+// Returns the bond that must be forfeited, if any.
+fn verify_closing(index: usize) -> Result<Option<([u8; 32], u64)>, Error> {
+    let vote_meta = load_vote_meta(index, Source::GroupInput)?;
+    let policy = vote_meta
+        .closure_policy_opt()
+        .meta("closure_policy")?
+        .unwrap_or(0);
+    let bond = vote_meta.proposer_bond_opt().meta("proposer_bond")?;
+    // a bond is always locked until the session end
+    if bond.is_some() || policy & CLOSE_AFTER_END != 0 {
+        let end_time = Since::new(vote_meta.end_time().meta("end_time")?);
+        let since = Since::new(load_input_since(index, Source::GroupInput)?);
+        if !since_reached(&since, &end_time) && !header_dep_reached(&end_time) {
+            #[cfg(feature = "enable_log")]
+            log::info!("vote session is still live");
+            return Err(Error::EarlyConsumption);
+        }
+    }
+    if policy & CLOSE_BY_COMMITTEE != 0 {
+        verify_committee(&vote_meta)?;
+    }
+    let (Some(bond), Some(forfeit_lock_hash)) = (
+        bond,
+        vote_meta
+            .bond_forfeit_lock_hash_opt()
+            .meta("bond_forfeit_lock_hash")?,
+    ) else {
+        return Ok(None);
+    };
+    // Quorum is only known from a result cell created in this transaction, by
+    // the tally cell pinned by the session.
+    let Some(tally_type_hash) = vote_meta.tally_type_hash_opt().meta("tally_type_hash")? else {
+        return Ok(Some((forfeit_lock_hash, bond)));
+    };
+    let session_id = blake160(load_input_out_point(index, Source::GroupInput)?.as_slice());
+    if let Some(result) = find_result(&session_id, Some(&tally_type_hash), Source::Output)? {
+        if result.quorum_reached().vote_result("quorum_reached")? != 0 {
+            return Ok(None);
+        }
+    }
+    Ok(Some((forfeit_lock_hash, bond)))
+}
+
+fn paid_to(lock_hash: &[u8; 32]) -> Result<u64, Error> {
+    let mut paid: u64 = 0;
+    for (index, hash) in QueryIter::new(load_cell_lock_hash, Source::Output).enumerate() {
+        if hash == *lock_hash {
+            let capacity = load_cell_capacity(index, Source::Output)?;
+            paid = paid.checked_add(capacity).ok_or(Error::WrongBond)?;
+        }
+    }
+    Ok(paid)
+}
+
+pub(crate) fn entry() -> Result<(), Error> {
+    let output_count = QueryIter::new(load_cell_type, Source::GroupOutput).count();
+    for index in 0..output_count {
+        verify_opening(index)?;
+    }
+    let input_count = QueryIter::new(load_cell_type, Source::GroupInput).count();
+    let mut forfeits: Vec<([u8; 32], u64)> = Vec::new();
+    for index in 0..input_count {
+        let Some((lock_hash, bond)) = verify_closing(index)? else {
+            continue;
+        };
+        match forfeits.iter_mut().find(|(hash, _)| *hash == lock_hash) {
+            Some((_, total)) => *total = total.checked_add(bond).ok_or(Error::WrongBond)?,
+            None => forfeits.push((lock_hash, bond)),
+        }
+    }
+    for (lock_hash, total) in forfeits {
+        if paid_to(&lock_hash)? < total {
+            #[cfg(feature = "enable_log")]
+            log::info!("proposer bond is not forfeited");
+            return Err(Error::WrongBond);
+        }
+    }
+    Ok(())
+}
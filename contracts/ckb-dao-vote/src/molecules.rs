@@ -1,16 +1,14 @@
-#[allow(clippy::all, unused_imports, dead_code)]
-mod ckb;
-#[allow(clippy::all, unused_imports, dead_code)]
-mod vote;
-
-use crate::error::Error;
+use crate::error::{DataKind, Error, ReadCause, ReadField};
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use ckb_std::{ckb_constants::Source, error::SysError, syscalls};
 
-pub use ckb::*;
+pub use ckb_dao_vote_types::molecules::{ckb::*, vote, vote::*};
 pub use molecule::lazy_reader::{Cursor, Error as MoleculeError, Read};
-pub use vote::*;
 
+// The readers probe the total size on construction, so that a missing cell or
+// witness fails there with its `SysError`. Later reads of the same data can
+// only fail on offsets, which are reported as `OutOfBound`.
 fn read_data<F: Fn(&mut [u8], usize) -> Result<usize, SysError>>(
     load_func: F,
     buf: &mut [u8],
@@ -24,21 +22,17 @@ fn read_data<F: Fn(&mut [u8], usize) -> Result<usize, SysError>>(
         Ok(l) => Ok(l),
         Err(err) => match err {
             SysError::LengthNotEnough(_) => Ok(buf.len()),
-            _ => Err(MoleculeError::OutOfBound(0, 0)),
+            _ => Err(MoleculeError::OutOfBound(offset, total_size)),
         },
     }
 }
 
-fn read_size<F: Fn(&mut [u8]) -> Result<usize, SysError>>(
-    load_func: F,
-) -> Result<usize, MoleculeError> {
+fn read_size<F: Fn(&mut [u8]) -> Result<usize, SysError>>(load_func: F) -> Result<usize, SysError> {
     let mut buf = [0u8; 4];
     match load_func(&mut buf) {
         Ok(l) => Ok(l),
-        Err(e) => match e {
-            SysError::LengthNotEnough(l) => Ok(l),
-            _ => Err(MoleculeError::OutOfBound(0, 0)),
-        },
+        Err(SysError::LengthNotEnough(l)) => Ok(l),
+        Err(e) => Err(e),
     }
 }
 
@@ -49,13 +43,13 @@ struct DataReader {
 }
 
 impl DataReader {
-    fn new(index: usize, source: Source) -> Self {
-        let total_size = read_size(|buf| syscalls::load_cell_data(buf, 0, index, source)).unwrap();
-        Self {
+    fn new(index: usize, source: Source) -> Result<Self, SysError> {
+        let total_size = read_size(|buf| syscalls::load_cell_data(buf, 0, index, source))?;
+        Ok(Self {
             total_size,
             source,
             index,
-        }
+        })
     }
 }
 
@@ -81,9 +75,9 @@ struct TxReader {
 }
 
 impl TxReader {
-    fn new() -> Self {
-        let total_size = read_size(|buf| syscalls::load_transaction(buf, 0)).unwrap();
-        Self { total_size }
+    fn new() -> Result<Self, SysError> {
+        let total_size = read_size(|buf| syscalls::load_transaction(buf, 0))?;
+        Ok(Self { total_size })
     }
 }
 
@@ -106,13 +100,13 @@ pub struct WitnessArgsReader {
 }
 
 impl WitnessArgsReader {
-    pub fn new(index: usize, source: Source) -> Self {
-        let total_size = read_size(|buf| syscalls::load_witness(buf, 0, index, source)).unwrap();
-        Self {
+    pub fn new(index: usize, source: Source) -> Result<Self, SysError> {
+        let total_size = read_size(|buf| syscalls::load_witness(buf, 0, index, source))?;
+        Ok(Self {
             total_size,
             source,
             index,
-        }
+        })
     }
 }
 
@@ -134,33 +128,77 @@ impl From<WitnessArgsReader> for Cursor {
 }
 
 fn load_witness_args(index: usize, source: Source) -> Result<vote::WitnessArgs, Error> {
-    let reader = WitnessArgsReader::new(index, source);
+    let reader = WitnessArgsReader::new(index, source).witness("witness")?;
     let cursor: Cursor = reader.into();
     let witness_args = WitnessArgs::from(cursor);
-    witness_args.verify(false)?;
+    witness_args.verify(false).witness("witness")?;
     Ok(witness_args)
 }
 
 pub fn load_vote_proof(index: usize) -> Result<vote::VoteProof, Error> {
     let witness_args = load_witness_args(index, Source::GroupOutput)?;
-    let output_type = witness_args.output_type()?;
-    let output_type = output_type.ok_or(Error::Molecule)?;
+    let output_type = witness_args
+        .output_type()
+        .witness("output_type")?
+        .ok_or(Error::Read {
+            kind: DataKind::Witness,
+            field: "output_type",
+            cause: ReadCause::Absent,
+        })?;
     let witness = VoteProof::from(output_type);
-    witness.verify(false)?;
+    witness.verify(false).witness("output_type")?;
     Ok(witness)
 }
 
-pub fn load_vote_meta(index: usize) -> Result<VoteMeta, Error> {
-    let reader = DataReader::new(index, Source::CellDep);
+// The voted set proof of a tally update, in the `input_type` of the witness
+// at the tally input's index.
+pub fn load_tally_proof(index: usize) -> Result<Vec<u8>, Error> {
+    let witness_args = load_witness_args(index, Source::Input)?;
+    let input_type = witness_args
+        .input_type()
+        .witness("input_type")?
+        .ok_or(Error::Read {
+            kind: DataKind::Witness,
+            field: "input_type",
+            cause: ReadCause::Absent,
+        })?;
+    input_type.try_into().witness("input_type")
+}
+
+fn load_data<T: From<Cursor>>(
+    index: usize,
+    source: Source,
+    kind: DataKind,
+    field: &'static str,
+) -> Result<T, Error> {
+    let reader = DataReader::new(index, source).field(kind, field)?;
     let cursor: Cursor = reader.into();
-    let data = VoteMeta::from(cursor);
-    data.verify(true)?;
+    Ok(T::from(cursor))
+}
+
+pub fn load_vote_meta(index: usize, source: Source) -> Result<VoteMeta, Error> {
+    let data: VoteMeta = load_data(index, source, DataKind::VoteMeta, "vote_meta")?;
+    data.verify_compatible().meta("vote_meta")?;
+
+    Ok(data)
+}
+
+pub fn load_tally(index: usize, source: Source) -> Result<Tally, Error> {
+    let data: Tally = load_data(index, source, DataKind::Tally, "tally")?;
+    data.verify(false).tally("tally")?;
+
+    Ok(data)
+}
+
+pub fn load_vote_result(index: usize, source: Source) -> Result<VoteResult, Error> {
+    let data: VoteResult = load_data(index, source, DataKind::VoteResult, "vote_result")?;
+    data.verify(false).vote_result("vote_result")?;
 
     Ok(data)
 }
 
 pub fn load_tx() -> Result<Transaction, Error> {
-    let reader = TxReader::new();
+    let reader = TxReader::new().tx("transaction")?;
     let cursor: Cursor = reader.into();
     let tx = Transaction::from(cursor);
 
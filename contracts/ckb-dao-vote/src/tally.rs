@@ -0,0 +1,467 @@
+use crate::entry::{find_meta_cell_dep, is_vote_args, vote_meta_version};
+use crate::error::{DataKind, Error, ReadField};
+use crate::molecules::{
+    Uint64Vec, VoteMeta, VoteResult, load_tally, load_tally_proof, load_vote_meta, load_vote_result,
+};
+use alloc::vec;
+use alloc::vec::Vec;
+use ckb_dao_vote_types::constants::{
+    BPS, ESCROW_LOCK_ARGS_LEN, RESULT_ARGS_LEN, SMT_VALUE, TALLY_ARGS_LEN, VOTE_META_V1,
+    VOTE_META_V2, WEIGHT_MODE_BALLOT, WEIGHT_MODE_CAPACITY, WEIGHT_MODE_ESCROW,
+};
+use ckb_dao_vote_types::hash::{blake2b_256, blake160};
+use ckb_std::ckb_constants::Source;
+use ckb_std::ckb_types::packed::Script;
+use ckb_std::ckb_types::prelude::{Builder, Entity, Pack};
+use ckb_std::high_level::{
+    QueryIter, load_cell_capacity, load_cell_data, load_cell_lock, load_cell_type,
+    load_cell_type_hash, load_input_out_point, load_script, load_script_hash,
+};
+use ckb_std::since::{LockValue, Since};
+use ckb_std::type_id::check_type_id;
+use sparse_merkle_tree::SMTBuilder;
+
+pub(crate) fn ballot_weight(
+    vote_meta: &VoteMeta,
+    weight_mode: u8,
+    index: usize,
+) -> Result<u64, Error> {
+    match weight_mode {
+        WEIGHT_MODE_BALLOT => Ok(1),
+        WEIGHT_MODE_CAPACITY => Ok(load_cell_capacity(index, Source::GroupOutput)?),
+        WEIGHT_MODE_ESCROW => escrow_weight(vote_meta, index),
+        _ => Err(Error::WrongWeightMode),
+    }
+}
+
+// The vote cell itself is the escrow: its lock keeps it for at least
+// `escrow_min_epochs` after creation. The weight grows linearly with the lock
+// duration, up to `escrow_max_epochs`. Durations are compared in units of
+// 1/length epoch, so the fraction of a relative epoch since counts as well.
+fn escrow_weight(vote_meta: &VoteMeta, index: usize) -> Result<u64, Error> {
+    let code_hash = vote_meta
+        .escrow_lock_code_hash_opt()
+        .meta("escrow_lock_code_hash")?
+        .ok_or(Error::WrongWeightMode)?;
+    let hash_type = vote_meta
+        .escrow_lock_hash_type_opt()
+        .meta("escrow_lock_hash_type")?
+        .ok_or(Error::WrongWeightMode)?;
+    let min_epochs = vote_meta
+        .escrow_min_epochs_opt()
+        .meta("escrow_min_epochs")?
+        .unwrap_or(0);
+    let max_epochs = vote_meta
+        .escrow_max_epochs_opt()
+        .meta("escrow_max_epochs")?
+        .unwrap_or(u64::MAX);
+    if min_epochs == 0 || max_epochs < min_epochs {
+        return Err(Error::WrongWeightMode);
+    }
+    let lock = load_cell_lock(index, Source::GroupOutput)?;
+    let args = lock.args().raw_data();
+    if lock.code_hash().as_slice() != code_hash
+        || lock.hash_type().as_slice() != [hash_type]
+        || args.len() != ESCROW_LOCK_ARGS_LEN
+    {
+        return Err(Error::WrongBallotWeight);
+    }
+    let since = Since::new(u64::from_le_bytes(args[20..].try_into().unwrap()));
+    let epoch = match since.extract_lock_value() {
+        Some(LockValue::EpochNumberWithFraction(epoch)) if !since.is_absolute() => epoch,
+        _ => return Err(Error::WrongBallotWeight),
+    };
+    // a zero length with a zero index counts whole epochs
+    let (epoch_index, length) = match (epoch.index(), epoch.length()) {
+        (0, 0) => (0, 1),
+        (epoch_index, length) if epoch_index < length => (epoch_index, length),
+        _ => return Err(Error::WrongBallotWeight),
+    };
+    let length = u128::from(length);
+    let locked = u128::from(epoch.number()) * length + u128::from(epoch_index);
+    let min_locked = u128::from(min_epochs) * length;
+    if locked < min_locked {
+        #[cfg(feature = "enable_log")]
+        log::info!("vote cell is locked for less than {} epochs", min_epochs);
+        return Err(Error::WrongBallotWeight);
+    }
+    let locked = locked.min(u128::from(max_epochs) * length);
+    let capacity = load_cell_capacity(index, Source::GroupOutput)?;
+    let weight = u128::from(capacity) * locked / min_locked;
+    u64::try_from(weight).map_err(|_| Error::WrongBallotWeight)
+}
+
+fn read_counts(counts: Uint64Vec, kind: DataKind) -> Result<Vec<u64>, Error> {
+    let mut result = Vec::new();
+    for index in 0..counts.len().field(kind, "counts")? {
+        result.push(counts.get(index).field(kind, "counts")?);
+    }
+    Ok(result)
+}
+
+fn find_single_cell(type_hash: &[u8; 32], source: Source) -> Result<usize, Error> {
+    let mut iter = QueryIter::new(load_cell_type_hash, source)
+        .enumerate()
+        .filter(|(_, hash)| hash.as_ref() == Some(type_hash))
+        .map(|(index, _)| index);
+    match (iter.next(), iter.next()) {
+        (Some(index), None) => Ok(index),
+        _ => Err(Error::WrongTally),
+    }
+}
+
+// The voted set of a tally is an SMT keyed by the voter's lock script hash,
+// with the leaf encoding of the eligibility SMT. A single proof shows that the
+// voters of the transaction are absent from the input root and present in the
+// output root, so every voter is counted at most once.
+fn verify_voted_set(
+    input_root: [u8; 32],
+    output_root: [u8; 32],
+    voters: &mut [[u8; 32]],
+    proof: &[u8],
+) -> Result<(), Error> {
+    voters.sort_unstable();
+    if voters.windows(2).any(|pair| pair[0] == pair[1]) {
+        #[cfg(feature = "enable_log")]
+        log::info!("a voter has more than one ballot in the transaction");
+        return Err(Error::WrongTally);
+    }
+    let mut absent = SMTBuilder::new();
+    let mut present = SMTBuilder::new();
+    for voter in voters.iter() {
+        absent = absent
+            .insert(&(*voter).into(), &[0u8; 32].into())
+            .map_err(|_| Error::WrongTally)?;
+        present = present
+            .insert(&(*voter).into(), &SMT_VALUE.into())
+            .map_err(|_| Error::WrongTally)?;
+    }
+    let absent = absent.build().map_err(|_| Error::WrongTally)?;
+    let present = present.build().map_err(|_| Error::WrongTally)?;
+    if absent.verify(&input_root.into(), proof).is_err()
+        || present.verify(&output_root.into(), proof).is_err()
+    {
+        #[cfg(feature = "enable_log")]
+        log::info!("a voter has already voted, or the voted set proof is wrong");
+        return Err(Error::WrongTally);
+    }
+    Ok(())
+}
+
+// Called by the vote type script: the tally cell pinned by the vote meta cell
+// must be updated by exactly the ballots of the script group, each voter at
+// most once over the whole session.
+pub(crate) fn verify_tally_update(
+    tally_type_hash: &[u8; 32],
+    session_id: &[u8; 20],
+    candidates_size: usize,
+    ballots: &[([u8; 32], u32, u64)],
+) -> Result<(), Error> {
+    let input_index = find_single_cell(tally_type_hash, Source::Input)?;
+    let output = find_single_cell(tally_type_hash, Source::Output)?;
+    let input = load_tally(input_index, Source::Input)?;
+    let output = load_tally(output, Source::Output)?;
+    if input.session_id().tally("session_id")? != *session_id
+        || output.session_id().tally("session_id")? != *session_id
+    {
+        return Err(Error::WrongTally);
+    }
+    let mut counts = read_counts(input.counts().tally("counts")?, DataKind::Tally)?;
+    if counts.len() != candidates_size {
+        return Err(Error::WrongTally);
+    }
+    let mut turnout = input.turnout().tally("turnout")?;
+    let mut abstain = input.abstain().tally("abstain")?;
+    for (_, choices, weight) in ballots {
+        if *choices == 0 {
+            abstain = abstain.checked_add(*weight).ok_or(Error::WrongTally)?;
+        }
+        for (bit, count) in counts.iter_mut().enumerate() {
+            if choices & (1 << bit) != 0 {
+                *count = count.checked_add(*weight).ok_or(Error::WrongTally)?;
+            }
+        }
+        turnout = turnout.checked_add(*weight).ok_or(Error::WrongTally)?;
+    }
+    if read_counts(output.counts().tally("counts")?, DataKind::Tally)? != counts
+        || output.turnout().tally("turnout")? != turnout
+        || output.abstain().tally("abstain")? != abstain
+    {
+        #[cfg(feature = "enable_log")]
+        log::info!("tally doesn't match the ballots");
+        return Err(Error::WrongTally);
+    }
+    let mut voters: Vec<[u8; 32]> = ballots.iter().map(|(voter, _, _)| *voter).collect();
+    verify_voted_set(
+        input.voted_root().tally("voted_root")?,
+        output.voted_root().tally("voted_root")?,
+        &mut voters,
+        &load_tally_proof(input_index)?,
+    )
+}
+
+fn verify_creation() -> Result<(), Error> {
+    let tally = load_tally(0, Source::GroupOutput)?;
+    let session_id = tally.session_id().tally("session_id")?;
+    let position = find_meta_cell_dep(&session_id)?.ok_or(Error::NoMetaCell)?;
+    let vote_meta = load_vote_meta(position, Source::CellDep)?;
+    if !matches!(vote_meta_version(&vote_meta)?, VOTE_META_V1 | VOTE_META_V2) {
+        return Err(Error::UnsupportedVersion);
+    }
+    // the vote meta cell must pin this tally cell
+    if vote_meta.tally_type_hash_opt().meta("tally_type_hash")? != Some(load_script_hash()?) {
+        return Err(Error::WrongTally);
+    }
+    let candidates_size = vote_meta
+        .candidates()
+        .meta("candidates")?
+        .len()
+        .meta("candidates")?;
+    if read_counts(tally.counts().tally("counts")?, DataKind::Tally)? != vec![0; candidates_size]
+        || tally.turnout().tally("turnout")? != 0
+        || tally.abstain().tally("abstain")? != 0
+        || tally.voted_root().tally("voted_root")? != [0; 32]
+    {
+        return Err(Error::WrongTally);
+    }
+    Ok(())
+}
+
+fn verify_update() -> Result<(), Error> {
+    let session_id = load_tally(0, Source::GroupInput)?
+        .session_id()
+        .tally("session_id")?;
+    if load_tally(0, Source::GroupOutput)?
+        .session_id()
+        .tally("session_id")?
+        != session_id
+    {
+        return Err(Error::WrongTally);
+    }
+    // The tally can only move together with new vote cells of its session.
+    // The vote type script then checks the counters.
+    let current_script = load_script()?;
+    let has_votes = QueryIter::new(load_cell_type, Source::Output)
+        .flatten()
+        .any(|script| {
+            is_same_code(&script, &current_script)
+                && is_vote_args(&script.args().raw_data(), &session_id)
+        });
+    if !has_votes {
+        return Err(Error::WrongTally);
+    }
+    // a result cell can only be created by finalization
+    let mut result_args = current_script.args().raw_data().to_vec();
+    result_args.push(0);
+    if QueryIter::new(load_cell_type, Source::Output)
+        .flatten()
+        .any(|script| {
+            is_same_code(&script, &current_script)
+                && script.args().raw_data()[..] == result_args[..]
+        })
+    {
+        return Err(Error::WrongResult);
+    }
+    Ok(())
+}
+
+fn is_same_code(script: &Script, current_script: &Script) -> bool {
+    script.code_hash() == current_script.code_hash()
+        && script.hash_type() == current_script.hash_type()
+}
+
+// Candidates with the highest count, in the same bit layout as a ballot.
+fn winners(counts: &[u64]) -> u32 {
+    let max = counts.iter().copied().max().unwrap_or(0);
+    if max == 0 {
+        return 0;
+    }
+    counts
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count == max)
+        .fold(0, |winners, (index, _)| winners | (1 << index))
+}
+
+// Both quorums must be reached when set. The relative quorum can't be reached
+// without `eligible_weight`.
+fn quorum_reached(vote_meta: &VoteMeta, turnout: u64) -> Result<bool, Error> {
+    if vote_meta
+        .quorum_opt()
+        .meta("quorum")?
+        .is_some_and(|quorum| turnout < quorum)
+    {
+        return Ok(false);
+    }
+    if let Some(quorum_bps) = vote_meta.quorum_bps_opt().meta("quorum_bps")? {
+        let Some(eligible_weight) = vote_meta.eligible_weight_opt().meta("eligible_weight")? else {
+            return Ok(false);
+        };
+        if u128::from(turnout) * BPS < u128::from(quorum_bps) * u128::from(eligible_weight) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+// A single winner is required. Its share of the non-abstaining turnout must
+// exceed the pass threshold when set.
+fn threshold_met(
+    vote_meta: &VoteMeta,
+    winners: u32,
+    counts: &[u64],
+    support: u64,
+) -> Result<bool, Error> {
+    if winners.count_ones() != 1 {
+        return Ok(false);
+    }
+    let count = counts[winners.trailing_zeros() as usize];
+    match vote_meta
+        .pass_threshold_bps_opt()
+        .meta("pass_threshold_bps")?
+    {
+        Some(threshold) => {
+            Ok(u128::from(count) * BPS > u128::from(threshold) * u128::from(support))
+        }
+        None => Ok(true),
+    }
+}
+
+fn verify_finalization() -> Result<(), Error> {
+    let tally = load_tally(0, Source::GroupInput)?;
+    let session_id = tally.session_id().tally("session_id")?;
+    // the vote meta cell must be consumed: the session is closed
+    let meta_index = QueryIter::new(load_input_out_point, Source::Input)
+        .position(|out_point| blake160(out_point.as_slice()) == session_id)
+        .ok_or(Error::WrongResult)?;
+    let meta_hash = blake2b_256(load_cell_data(meta_index, Source::Input)?);
+    let vote_meta = load_vote_meta(meta_index, Source::Input)?;
+    if vote_meta.tally_type_hash_opt().meta("tally_type_hash")? != Some(load_script_hash()?) {
+        return Err(Error::WrongResult);
+    }
+
+    let current_script = load_script()?;
+    let mut result_args = current_script.args().raw_data().to_vec();
+    result_args.push(0);
+    let mut iter = QueryIter::new(load_cell_type, Source::Output)
+        .enumerate()
+        .filter(|(_, script)| {
+            script.as_ref().is_some_and(|script| {
+                is_same_code(script, &current_script)
+                    && script.args().raw_data()[..] == result_args[..]
+            })
+        })
+        .map(|(index, _)| index);
+    let index = match (iter.next(), iter.next()) {
+        (Some(index), None) => index,
+        _ => return Err(Error::WrongResult),
+    };
+    let result = load_vote_result(index, Source::Output)?;
+    let counts = read_counts(tally.counts().tally("counts")?, DataKind::Tally)?;
+    let turnout = tally.turnout().tally("turnout")?;
+    let abstain = tally.abstain().tally("abstain")?;
+    let support = turnout.checked_sub(abstain).ok_or(Error::WrongResult)?;
+    let winners = winners(&counts);
+    let quorum_reached = quorum_reached(&vote_meta, turnout)?;
+    let passed = quorum_reached && threshold_met(&vote_meta, winners, &counts, support)?;
+    if result.session_id().vote_result("session_id")? != session_id
+        || result.meta_hash().vote_result("meta_hash")? != meta_hash
+        || result.winners().vote_result("winners")? != winners
+        || read_counts(result.counts().vote_result("counts")?, DataKind::VoteResult)? != counts
+        || result.turnout().vote_result("turnout")? != turnout
+        || result.abstain().vote_result("abstain")? != abstain
+        || result.quorum_reached().vote_result("quorum_reached")? != u8::from(quorum_reached)
+        || result.passed().vote_result("passed")? != u8::from(passed)
+    {
+        #[cfg(feature = "enable_log")]
+        log::info!("vote result doesn't match the tally");
+        return Err(Error::WrongResult);
+    }
+    Ok(())
+}
+
+pub(crate) fn entry() -> Result<(), Error> {
+    check_type_id(0)?;
+    let input_count = QueryIter::new(load_cell_type, Source::GroupInput).count();
+    let output_count = QueryIter::new(load_cell_type, Source::GroupOutput).count();
+    match (input_count, output_count) {
+        (0, 1) => verify_creation(),
+        (1, 1) => verify_update(),
+        (1, 0) => verify_finalization(),
+        _ => Err(Error::WrongTally),
+    }
+}
+
+// Result cells are created by finalization only, and can never be consumed.
+pub(crate) fn result_entry() -> Result<(), Error> {
+    if QueryIter::new(load_cell_type, Source::GroupInput).count() > 0 {
+        return Err(Error::WrongResult);
+    }
+    let current_script = load_script()?;
+    let args = current_script.args().raw_data();
+    if args[TALLY_ARGS_LEN] != 0 {
+        return Err(Error::WrongResult);
+    }
+    // The tally cell validates the content of the result cell on finalization,
+    // when it is consumed without a successor.
+    let is_tally = |script: &Script| {
+        is_same_code(script, &current_script)
+            && script.args().raw_data()[..] == args[0..TALLY_ARGS_LEN]
+    };
+    let consumed = QueryIter::new(load_cell_type, Source::Input)
+        .flatten()
+        .filter(is_tally)
+        .count();
+    let updated = QueryIter::new(load_cell_type, Source::Output)
+        .flatten()
+        .any(|script| is_tally(&script));
+    if consumed != 1 || updated {
+        return Err(Error::WrongResult);
+    }
+    Ok(())
+}
+
+// A result cell of the session in cell_deps proves that the session is closed.
+// The vote meta cell is gone by then, so the result is trusted through its
+// tally: only the finalization of the tally pinned by the session's vote meta
+// cell can create it.
+pub(crate) fn has_result_cell_dep(session_id: &[u8; 20]) -> Result<bool, Error> {
+    Ok(find_result(session_id, None, Source::CellDep)?.is_some())
+}
+
+// Finds the result cell of the session. With `tally_type_hash`, the result
+// must also belong to that tally cell.
+pub(crate) fn find_result(
+    session_id: &[u8; 20],
+    tally_type_hash: Option<&[u8; 32]>,
+    source: Source,
+) -> Result<Option<VoteResult>, Error> {
+    let current_script = load_script()?;
+    for (index, script) in QueryIter::new(load_cell_type, source).enumerate() {
+        let Some(script) = script else {
+            continue;
+        };
+        let args = script.args().raw_data();
+        if !is_same_code(&script, &current_script)
+            || args.len() != RESULT_ARGS_LEN
+            || args[TALLY_ARGS_LEN] != 0
+        {
+            continue;
+        }
+        if let Some(tally_type_hash) = tally_type_hash {
+            let tally_script = script
+                .clone()
+                .as_builder()
+                .args(args[0..TALLY_ARGS_LEN].pack())
+                .build();
+            if blake2b_256(tally_script.as_slice()) != *tally_type_hash {
+                continue;
+            }
+        }
+        let result = load_vote_result(index, source)?;
+        if result.session_id().vote_result("session_id")? == *session_id {
+            return Ok(Some(result));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,105 @@
+//! Minimal read-only JSON endpoint over HTTP/1.0, serving one request at a
+//! time:
+//!
+//! - `GET /sessions` lists session ids with ballots
+//! - `GET /sessions/live` lists session ids of live vote meta cells
+//! - `GET /sessions/<session id>/status`
+//! - `GET /sessions/<session id>/ballots`
+//! - `GET /sessions/<session id>/voters/<lock hash>` answers `{"has_voted": bool}`
+//!
+//! Ids and hashes are hex, with or without `0x`.
+
+use crate::Indexer;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+fn parse_hex<const N: usize>(s: &str) -> Option<[u8; N]> {
+    hex::decode(s.trim_start_matches("0x"))
+        .ok()?
+        .try_into()
+        .ok()
+}
+
+/// Answers a request path with a status code and a JSON body.
+pub fn route(indexer: &Indexer, path: &str) -> (u16, Value) {
+    let not_found = (404, json!({ "error": "not found" }));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["sessions"] => {
+            let sessions: Vec<String> = indexer
+                .sessions()
+                .map(|id| format!("0x{}", hex::encode(id)))
+                .collect();
+            (200, json!(sessions))
+        }
+        ["sessions", "live"] => {
+            let sessions: Vec<String> = indexer
+                .live_sessions()
+                .iter()
+                .map(|id| format!("0x{}", hex::encode(id)))
+                .collect();
+            (200, json!(sessions))
+        }
+        ["sessions", id, rest @ ..] => {
+            let Some(session_id) = parse_hex::<20>(id) else {
+                return (400, json!({ "error": "invalid session id" }));
+            };
+            match rest {
+                ["status"] => match indexer.session_status(&session_id) {
+                    Some(status) => (200, json!(status)),
+                    None => not_found,
+                },
+                ["ballots"] => (200, json!(indexer.ballots(&session_id))),
+                ["voters", lock_hash] => match parse_hex::<32>(lock_hash) {
+                    Some(lock_hash) => (
+                        200,
+                        json!({ "has_voted": indexer.has_voted(&session_id, &lock_hash) }),
+                    ),
+                    None => (400, json!({ "error": "invalid lock hash" })),
+                },
+                _ => not_found,
+            }
+        }
+        _ => not_found,
+    }
+}
+
+fn handle(indexer: &Indexer, mut stream: TcpStream) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(path)) => route(indexer, path),
+        _ => (405, json!({ "error": "method not allowed" })),
+    };
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.0 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        status,
+        if status == 200 { "OK" } else { "Error" },
+        body.len(),
+        body
+    )
+}
+
+/// Serves the endpoint. Fails only if `addr` can't be bound, a failed
+/// connection is logged and skipped.
+pub fn serve(indexer: &Indexer, addr: impl ToSocketAddrs) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("accept failed: {}", err);
+                continue;
+            }
+        };
+        if let Err(err) = handle(indexer, stream) {
+            eprintln!("request failed: {}", err);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! JSON input of the indexer.
+//!
+//! A file holds one item or an array of items. An item is either a block in
+//! the format returned by the `get_block` RPC, or a loose transaction with the
+//! block that committed it:
+//!
+//! ```json
+//! { "block_number": 100, "timestamp": 0, "epoch": 0, "tx_index": 1, "transaction": { ... } }
+//! ```
+//!
+//! `timestamp` is in milliseconds and `epoch` is the full value of the
+//! block's EpochNumberWithFraction. Both default to 0, as does `tx_index`.
+
+use ckb_dao_vote_tally::BlockInfo;
+use ckb_jsonrpc_types as json;
+use ckb_types::{core, packed, prelude::*};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct LooseTransaction {
+    pub block_number: u64,
+    #[serde(default)]
+    pub timestamp: u64,
+    #[serde(default)]
+    pub epoch: u64,
+    #[serde(default)]
+    pub tx_index: usize,
+    pub transaction: json::Transaction,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Item {
+    Block(json::BlockView),
+    Transaction(LooseTransaction),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Items {
+    Many(Vec<Item>),
+    One(Item),
+}
+
+impl Item {
+    /// Transactions of the item with the block that committed them.
+    pub fn transactions(self) -> Vec<(core::TransactionView, BlockInfo)> {
+        match self {
+            Item::Block(block) => {
+                let block: core::BlockView = block.into();
+                let header = block.header();
+                block
+                    .transactions()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(tx_index, tx)| {
+                        let info = BlockInfo {
+                            number: header.number(),
+                            timestamp: header.timestamp(),
+                            epoch: header.epoch().full_value(),
+                            tx_index,
+                        };
+                        (tx, info)
+                    })
+                    .collect()
+            }
+            Item::Transaction(loose) => {
+                let info = BlockInfo {
+                    number: loose.block_number,
+                    timestamp: loose.timestamp,
+                    epoch: loose.epoch,
+                    tx_index: loose.tx_index,
+                };
+                let tx: packed::Transaction = loose.transaction.into();
+                vec![(tx.into_view(), info)]
+            }
+        }
+    }
+}
+
+/// Parses the content of an input file.
+pub fn parse(json: &str) -> Result<Vec<(core::TransactionView, BlockInfo)>, serde_json::Error> {
+    let items = match serde_json::from_str(json)? {
+        Items::Many(items) => items,
+        Items::One(item) => vec![item],
+    };
+    Ok(items.into_iter().flat_map(Item::transactions).collect())
+}
@@ -0,0 +1,290 @@
+//! Local stand-in for an indexer of ckb-dao-vote sessions.
+//!
+//! Transactions are ingested in chain order from JSON files, see [`input`].
+//! The indexer keeps every cell they create, and indexes vote cells by
+//! session, so that a UI can be tested against realistic chains without a
+//! node.
+
+#[cfg(feature = "http")]
+pub mod http;
+pub mod input;
+
+use ckb_dao_vote_tally::{since_reached, BlockInfo};
+use ckb_dao_vote_types::constants::{BITMAP_LEN, GUARDED_ARGS_LEN, VOTE_ARGS_LEN};
+use ckb_dao_vote_types::hash::blake160;
+use ckb_dao_vote_types::molecules::vote::{VoteMeta, VoteProof};
+use ckb_types::{
+    bytes::Bytes,
+    core::{ScriptHashType, TransactionView},
+    packed::{Byte32, OutPoint, Script, WitnessArgs},
+    prelude::*,
+};
+use molecule::lazy_reader::Cursor;
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+
+fn hex_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+}
+
+fn out_point_string(out_point: &OutPoint) -> String {
+    let index: u32 = out_point.index().unpack();
+    format!(
+        "0x{}:{}",
+        hex::encode(out_point.tx_hash().as_slice()),
+        index
+    )
+}
+
+fn serialize_out_point<S: Serializer>(
+    out_point: &OutPoint,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&out_point_string(out_point))
+}
+
+/// A vote cell.
+#[derive(Clone, Debug, Serialize)]
+pub struct Ballot {
+    #[serde(serialize_with = "serialize_out_point")]
+    pub out_point: OutPoint,
+    /// `lock_script_hash` of the vote proof
+    #[serde(serialize_with = "hex_bytes")]
+    pub lock_hash: [u8; 32],
+    #[serde(serialize_with = "hex_bytes")]
+    pub data: Bytes,
+    pub capacity: u64,
+    pub block_number: u64,
+    pub tx_index: usize,
+    /// block number of the consuming transaction
+    pub consumed_at: Option<u64>,
+}
+
+impl Ballot {
+    /// The ballot bitmap, if the data is long enough.
+    pub fn choices(&self) -> Option<u32> {
+        Some(u32::from_le_bytes(
+            self.data.get(..BITMAP_LEN)?.try_into().ok()?,
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// ballots reference a vote meta cell that was not ingested
+    Unknown,
+    /// the tip is before `start_time`
+    Pending,
+    Open,
+    /// the tip is at or after `end_time`
+    Ended,
+    /// the vote meta cell is consumed
+    Closed,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SessionStatus {
+    pub phase: Phase,
+    pub meta_out_point: Option<String>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub ballots: usize,
+    pub live_ballots: usize,
+    /// distinct `lock_script_hash` of all ballots
+    pub voters: usize,
+}
+
+struct Cell {
+    data: Bytes,
+    consumed_at: Option<u64>,
+}
+
+pub struct Indexer {
+    code_hash: Byte32,
+    hash_type: ScriptHashType,
+    tip: BlockInfo,
+    cells: HashMap<OutPoint, Cell>,
+    // blake160 of the out point of every cell, i.e. its session id if it is a
+    // vote meta cell
+    session_ids: HashMap<[u8; 20], OutPoint>,
+    ballots: BTreeMap<[u8; 20], Vec<Ballot>>,
+    ballot_index: HashMap<OutPoint, ([u8; 20], usize)>,
+}
+
+impl Indexer {
+    /// `code_hash` and `hash_type` of the deployed ckb-dao-vote type script.
+    pub fn new(code_hash: Byte32, hash_type: ScriptHashType) -> Self {
+        Self {
+            code_hash,
+            hash_type,
+            tip: BlockInfo::default(),
+            cells: HashMap::new(),
+            session_ids: HashMap::new(),
+            ballots: BTreeMap::new(),
+            ballot_index: HashMap::new(),
+        }
+    }
+
+    pub fn tip(&self) -> &BlockInfo {
+        &self.tip
+    }
+
+    fn is_vote_script(&self, script: &Script) -> bool {
+        let args_len = script.args().raw_data().len();
+        script.code_hash() == self.code_hash
+            && script.hash_type() == self.hash_type.into()
+            && (args_len == VOTE_ARGS_LEN || args_len == GUARDED_ARGS_LEN)
+    }
+
+    fn lock_hash(tx: &TransactionView, index: usize) -> Option<[u8; 32]> {
+        let witness = tx.witnesses().get(index)?;
+        let witness_args = WitnessArgs::from_slice(&witness.raw_data()).ok()?;
+        let output_type = witness_args.output_type().to_opt()?;
+        let vote_proof = VoteProof::from(Cursor::from(output_type.raw_data().to_vec()));
+        vote_proof.verify(false).ok()?;
+        vote_proof.lock_script_hash().ok()
+    }
+
+    /// Ingests a committed transaction. Transactions must be ingested in
+    /// chain order.
+    pub fn ingest(&mut self, tx: &TransactionView, block: BlockInfo) {
+        if block.number >= self.tip.number {
+            self.tip = block;
+        }
+        for out_point in tx.input_pts_iter() {
+            if let Some(cell) = self.cells.get_mut(&out_point) {
+                cell.consumed_at = Some(block.number);
+            }
+            if let Some((session_id, index)) = self.ballot_index.get(&out_point) {
+                self.ballots.get_mut(session_id).unwrap()[*index].consumed_at = Some(block.number);
+            }
+        }
+        for (index, (output, data)) in tx.outputs_with_data_iter().enumerate() {
+            let out_point = OutPoint::new(tx.hash(), index as u32);
+            self.session_ids
+                .insert(blake160(out_point.as_slice()), out_point.clone());
+            self.cells.insert(
+                out_point.clone(),
+                Cell {
+                    data: data.clone(),
+                    consumed_at: None,
+                },
+            );
+            let Some(type_script) = output.type_().to_opt() else {
+                continue;
+            };
+            if !self.is_vote_script(&type_script) {
+                continue;
+            }
+            // vote cells on chain always carry a valid vote proof
+            let Some(lock_hash) = Self::lock_hash(tx, index) else {
+                continue;
+            };
+            let session_id: [u8; 20] = type_script.args().raw_data()[..VOTE_ARGS_LEN]
+                .try_into()
+                .unwrap();
+            let ballots = self.ballots.entry(session_id).or_default();
+            self.ballot_index
+                .insert(out_point.clone(), (session_id, ballots.len()));
+            ballots.push(Ballot {
+                out_point,
+                lock_hash,
+                data,
+                capacity: output.capacity().unpack(),
+                block_number: block.number,
+                tx_index: block.tx_index,
+                consumed_at: None,
+            });
+        }
+    }
+
+    /// Ingests the content of an input file, see [`input`].
+    pub fn ingest_json(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        for (tx, block) in input::parse(json)? {
+            self.ingest(&tx, block);
+        }
+        Ok(())
+    }
+
+    /// Session ids with at least one ballot.
+    pub fn sessions(&self) -> impl Iterator<Item = &[u8; 20]> {
+        self.ballots.keys()
+    }
+
+    /// Session ids of the live vote meta cells, with or without ballots, in
+    /// ascending order.
+    pub fn live_sessions(&self) -> Vec<[u8; 20]> {
+        let mut sessions: Vec<[u8; 20]> = self
+            .session_ids
+            .iter()
+            .filter(|(_, out_point)| {
+                self.cells.get(*out_point).is_some_and(|cell| {
+                    cell.consumed_at.is_none()
+                        && VoteMeta::from(Cursor::from(cell.data.to_vec()))
+                            .verify_compatible()
+                            .is_ok()
+                })
+            })
+            .map(|(session_id, _)| *session_id)
+            .collect();
+        sessions.sort();
+        sessions
+    }
+
+    /// All ballots of a session in chain order, including consumed ones.
+    pub fn ballots(&self, session_id: &[u8; 20]) -> &[Ballot] {
+        self.ballots
+            .get(session_id)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    pub fn has_voted(&self, session_id: &[u8; 20], lock_hash: &[u8; 32]) -> bool {
+        self.ballots(session_id)
+            .iter()
+            .any(|ballot| ballot.lock_hash == *lock_hash)
+    }
+
+    /// Status of a session, `None` if neither the vote meta cell nor a ballot
+    /// of the session was ingested.
+    pub fn session_status(&self, session_id: &[u8; 20]) -> Option<SessionStatus> {
+        let ballots = self.ballots(session_id);
+        let meta_out_point = self.session_ids.get(session_id);
+        let meta = meta_out_point.and_then(|out_point| self.cells.get(out_point));
+        let times = meta.and_then(|cell| {
+            let vote_meta = VoteMeta::from(Cursor::from(cell.data.to_vec()));
+            vote_meta.verify_compatible().ok()?;
+            Some((vote_meta.start_time().ok()?, vote_meta.end_time().ok()?))
+        });
+        if ballots.is_empty() && times.is_none() {
+            return None;
+        }
+        let phase = match (meta, times) {
+            (Some(cell), _) if cell.consumed_at.is_some() => Phase::Closed,
+            (_, Some((start_time, _))) if !since_reached(&self.tip, start_time) => Phase::Pending,
+            (_, Some((_, end_time))) if end_time != 0 && since_reached(&self.tip, end_time) => {
+                Phase::Ended
+            }
+            (_, Some(_)) => Phase::Open,
+            _ => Phase::Unknown,
+        };
+        let mut voters: Vec<_> = ballots.iter().map(|ballot| ballot.lock_hash).collect();
+        voters.sort();
+        voters.dedup();
+        Some(SessionStatus {
+            phase,
+            meta_out_point: meta_out_point
+                .filter(|_| times.is_some())
+                .map(out_point_string),
+            start_time: times.map(|(start_time, _)| start_time),
+            end_time: times.map(|(_, end_time)| end_time),
+            ballots: ballots.len(),
+            live_ballots: ballots
+                .iter()
+                .filter(|ballot| ballot.consumed_at.is_none())
+                .count(),
+            voters: voters.len(),
+        })
+    }
+}
@@ -0,0 +1,112 @@
+use ckb_dao_vote_indexer::Indexer;
+use ckb_types::{core::ScriptHashType, packed::Byte32, prelude::*};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::process::exit;
+
+const USAGE: &str = "\
+Usage: ckb-dao-vote-indexer --code-hash <CODE_HASH> [--hash-type data|type|data1|data2] [--listen <ADDR>] <FILES>...
+
+Indexes the vote sessions of the transactions in FILES, in the given order, and
+prints the status of every session with ballots or a live vote meta cell as
+JSON.
+
+A file holds a block as returned by the get_block RPC, a transaction as
+{block_number, timestamp, epoch, tx_index, transaction}, or an array of them.
+CODE_HASH and the hash type (default: type) identify the vote type script.
+With --listen, the sessions are served as JSON over HTTP instead, which needs
+the http feature.";
+
+struct Options {
+    files: Vec<PathBuf>,
+    code_hash: Byte32,
+    hash_type: ScriptHashType,
+    listen: Option<String>,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut args = std::env::args().skip(1);
+    let mut files = Vec::new();
+    let mut code_hash = None;
+    let mut hash_type = ScriptHashType::Type;
+    let mut listen = None;
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value of {}", arg));
+        match arg.as_str() {
+            "--code-hash" => {
+                let value = value()?;
+                let bytes: [u8; 32] = hex::decode(value.trim_start_matches("0x"))
+                    .map_err(|_| "invalid code hash")?
+                    .try_into()
+                    .map_err(|_| "code hash must be 32 bytes")?;
+                code_hash = Some(bytes.pack());
+            }
+            "--hash-type" => {
+                hash_type = match value()?.as_str() {
+                    "data" => ScriptHashType::Data,
+                    "type" => ScriptHashType::Type,
+                    "data1" => ScriptHashType::Data1,
+                    "data2" => ScriptHashType::Data2,
+                    other => return Err(format!("unknown hash type {}", other)),
+                }
+            }
+            "--listen" => listen = Some(value()?),
+            "-h" | "--help" => return Err(String::new()),
+            _ if !arg.starts_with('-') => files.push(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+    if files.is_empty() {
+        return Err("missing files".into());
+    }
+    Ok(Options {
+        files,
+        code_hash: code_hash.ok_or("missing --code-hash")?,
+        hash_type,
+        listen,
+    })
+}
+
+fn run(options: Options) -> Result<(), Box<dyn std::error::Error>> {
+    let mut indexer = Indexer::new(options.code_hash, options.hash_type);
+    for file in &options.files {
+        indexer.ingest_json(&std::fs::read_to_string(file)?)?;
+    }
+    if let Some(addr) = options.listen {
+        #[cfg(feature = "http")]
+        return Ok(ckb_dao_vote_indexer::http::serve(&indexer, addr.as_str())?);
+        #[cfg(not(feature = "http"))]
+        return Err(format!("cannot listen on {}: built without the http feature", addr).into());
+    }
+    let ids: BTreeSet<[u8; 20]> = indexer
+        .sessions()
+        .copied()
+        .chain(indexer.live_sessions())
+        .collect();
+    let sessions: serde_json::Map<_, _> = ids
+        .iter()
+        .map(|id| {
+            let status = indexer.session_status(id);
+            (format!("0x{}", hex::encode(id)), serde_json::json!(status))
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&sessions)?);
+    Ok(())
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(reason) => {
+            if !reason.is_empty() {
+                eprintln!("error: {}\n", reason);
+            }
+            eprintln!("{}", USAGE);
+            exit(2);
+        }
+    };
+    if let Err(e) = run(options) {
+        eprintln!("error: {}", e);
+        exit(1);
+    }
+}
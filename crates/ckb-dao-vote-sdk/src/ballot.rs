@@ -0,0 +1,171 @@
+use crate::error::Error;
+use crate::session::VoteSession;
+use ckb_dao_vote_types::constants::{VOTE_META_V1, WEIGHT_MODE_BALLOT, WEIGHT_MODE_CAPACITY};
+use ckb_dao_vote_types::models::VoteProof;
+use ckb_types::{
+    bytes::Bytes,
+    core::{Capacity, TransactionView},
+    packed::{CellOutput, Script, WitnessArgs},
+    prelude::*,
+};
+
+/// Adds a vote cell to a transaction.
+///
+/// The voter's lock must also be used by one of the inputs, which is left to
+/// the caller. Sessions with a tally cell need the tally update as well.
+pub struct BallotBuilder<'a> {
+    session: &'a VoteSession,
+    lock: Script,
+    vote_lock: Option<Script>,
+    choices: u32,
+    // first index passed to `candidate` that doesn't fit in the bitmap
+    wrong_candidate: Option<usize>,
+    capacity: Capacity,
+    weight: Option<u64>,
+    smt_proof: Vec<u8>,
+}
+
+impl<'a> BallotBuilder<'a> {
+    /// `lock` is the voter's lock script, its hash is the `lock_script_hash`
+    /// of the vote proof. It is also the lock of the vote cell, unless
+    /// `vote_lock` is set.
+    pub fn new(session: &'a VoteSession, lock: Script) -> Self {
+        Self {
+            session,
+            lock,
+            vote_lock: None,
+            choices: 0,
+            wrong_candidate: None,
+            capacity: Capacity::zero(),
+            weight: None,
+            smt_proof: Vec::new(),
+        }
+    }
+
+    /// Bitmap of the chosen candidates: bit `i` for the candidate at index `i`.
+    pub fn choices(mut self, choices: u32) -> Self {
+        self.choices = choices;
+        self
+    }
+
+    /// Chooses the candidate at `index`. An index that is not below 32 or the
+    /// number of candidates fails `ballot` and `build` with `WrongCandidate`.
+    pub fn candidate(mut self, index: usize) -> Self {
+        if index < 32 {
+            self.choices |= 1 << index;
+        } else {
+            self.wrong_candidate.get_or_insert(index);
+        }
+        self
+    }
+
+    /// Lock of the vote cell, when it differs from the voter's lock, e.g. the
+    /// escrow lock of a session in the escrow weight mode.
+    pub fn vote_lock(mut self, vote_lock: Script) -> Self {
+        self.vote_lock = Some(vote_lock);
+        self
+    }
+
+    pub fn capacity(mut self, capacity: u64) -> Self {
+        self.capacity = Capacity::shannons(capacity);
+        self
+    }
+
+    /// The weight recorded in a version 2 ballot. Required for the escrow
+    /// weight mode, otherwise derived from the weight mode and, if set, it
+    /// must match: 1 per ballot, or the capacity in the capacity mode.
+    pub fn weight(mut self, weight: u64) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Compiled SMT proof of the voter's lock script hash, see
+    /// `smt_root_hash`.
+    pub fn smt_proof(mut self, smt_proof: Vec<u8>) -> Self {
+        self.smt_proof = smt_proof;
+        self
+    }
+
+    /// The vote cell data.
+    pub fn ballot(&self) -> Result<Bytes, Error> {
+        let vote_meta = self.session.vote_meta();
+        let candidates_size = vote_meta.candidates.len();
+        if let Some(index) = self.wrong_candidate {
+            return Err(Error::WrongCandidate(index));
+        }
+        let choices = self.choices;
+        if candidates_size > 32 {
+            return Err(Error::WrongBallot);
+        }
+        let highest_bit = 32 - choices.leading_zeros() as usize;
+        if highest_bit > candidates_size {
+            return Err(Error::WrongCandidate(highest_bit - 1));
+        }
+        // an empty bitmap is an abstain ballot
+        if choices == 0 && vote_meta.allow_abstain.unwrap_or(0) == 0 {
+            return Err(Error::WrongBallot);
+        }
+        let mut ballot = choices.to_le_bytes().to_vec();
+        if self.session.version() == VOTE_META_V1 {
+            if self.weight.is_some() {
+                return Err(Error::WrongBallotWeight);
+            }
+        } else {
+            let weight = match vote_meta.weight_mode.unwrap_or(WEIGHT_MODE_BALLOT) {
+                WEIGHT_MODE_BALLOT => 1,
+                WEIGHT_MODE_CAPACITY => self.capacity.as_u64(),
+                _ => self.weight.ok_or(Error::WrongBallotWeight)?,
+            };
+            if self.weight.is_some_and(|expected| expected != weight) {
+                return Err(Error::WrongBallotWeight);
+            }
+            ballot.extend_from_slice(&weight.to_le_bytes());
+        }
+        Ok(ballot.into())
+    }
+
+    /// Adds the vote cell, the vote meta cell dep if missing, and the vote
+    /// proof in the `output_type` of the witness at the vote cell's index.
+    pub fn build(self, tx: TransactionView) -> Result<TransactionView, Error> {
+        let ballot = self.ballot()?;
+        let vote_proof = VoteProof {
+            lock_script_hash: self.lock.calc_script_hash().unpack().0,
+            smt_proof: self.smt_proof,
+        };
+        let vote_proof = Bytes::from(serde_molecule::to_vec(&vote_proof, false)?);
+
+        let output = CellOutput::new_builder()
+            .capacity(self.capacity.pack())
+            .lock(self.vote_lock.unwrap_or(self.lock))
+            .type_(Some(self.session.type_script().clone()).pack())
+            .build();
+        let index = tx.outputs().len();
+        let mut witnesses: Vec<_> = tx.witnesses().into_iter().collect();
+        if witnesses.len() <= index {
+            witnesses.resize(index + 1, Bytes::new().pack());
+        }
+        let witness = &witnesses[index];
+        let witness_args = if witness.is_empty() {
+            WitnessArgs::default()
+        } else {
+            WitnessArgs::from_slice(&witness.raw_data()).map_err(|_| Error::WrongWitness)?
+        };
+        witnesses[index] = witness_args
+            .as_builder()
+            .output_type(Some(vote_proof).pack())
+            .build()
+            .as_bytes()
+            .pack();
+
+        let meta_cell_dep = self.session.meta_cell_dep();
+        let mut builder = tx.as_advanced_builder();
+        if !tx.cell_deps_iter().any(|dep| dep == meta_cell_dep) {
+            builder = builder.cell_dep(meta_cell_dep);
+        }
+        Ok(builder
+            .output(output)
+            .output_data(ballot.pack())
+            .set_witnesses(witnesses)
+            .build())
+    }
+}
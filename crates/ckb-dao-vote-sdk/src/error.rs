@@ -0,0 +1,32 @@
+use core::fmt::Display;
+
+#[derive(Debug)]
+pub enum Error {
+    // Molecule serialization of the vote meta or the vote proof failed
+    Serialize(serde_molecule::Error),
+    // the script of the session is not set
+    NoScript,
+    // the ballot would be rejected by the type script
+    WrongBallot,
+    // a chosen candidate index is not below 32 or the number of candidates
+    WrongCandidate(usize),
+    // the ballot weight is missing, doesn't match the weight mode, or is set
+    // for a version 1 session
+    WrongBallotWeight,
+    // the existing witness at the vote cell's index is not a WitnessArgs
+    WrongWitness,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<serde_molecule::Error> for Error {
+    fn from(e: serde_molecule::Error) -> Self {
+        Error::Serialize(e)
+    }
+}
@@ -0,0 +1,59 @@
+use ckb_dao_vote_types::error::ErrorCode;
+use core::fmt::Display;
+
+/// A failed ckb-dao-vote script, decoded from its exit code and, when parsed
+/// from a verification error message, the script group that failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScriptFailure {
+    /// e.g. `Outputs[3].Type`: the first cell of the failed script group
+    pub source: Option<String>,
+    pub code: i8,
+}
+
+impl ScriptFailure {
+    pub fn from_code(code: i8) -> Self {
+        Self { source: None, code }
+    }
+
+    /// Parses the `TransactionScriptError` of a node or ckb-testtool
+    /// verification error, e.g. `... { source: Outputs[3].Type, cause:
+    /// ValidationFailure: see error code 57 on page ... }`.
+    pub fn parse(message: &str) -> Option<Self> {
+        let start = message.find("error code ")? + "error code ".len();
+        let code: String = message[start..]
+            .chars()
+            .take_while(|c| *c == '-' || c.is_ascii_digit())
+            .collect();
+        let code = code.parse().ok()?;
+        let source = message.find("source: ").and_then(|start| {
+            let rest = &message[start + "source: ".len()..];
+            let end = rest.find(',')?;
+            Some(rest[..end].trim().to_string())
+        });
+        Some(Self { source, code })
+    }
+
+    /// `None` for codes not defined by the type script, e.g. those of other
+    /// scripts in the same transaction.
+    pub fn error(&self) -> Option<ErrorCode> {
+        ErrorCode::from_code(self.code)
+    }
+
+    pub fn reason(&self) -> &'static str {
+        self.error()
+            .map(ErrorCode::description)
+            .unwrap_or("unknown exit code")
+    }
+}
+
+impl Display for ScriptFailure {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if let Some(source) = &self.source {
+            write!(f, "{}: ", source)?;
+        }
+        match self.error() {
+            Some(error) => write!(f, "{:?} ({}): {}", error, self.code, self.reason()),
+            None => write!(f, "{}", self.reason()),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Builders for ckb-dao-vote transactions.
+//!
+//! The builders only add the vote specific parts (cells, cell deps, witnesses)
+//! to a `TransactionView`. Balancing, fees and signing are left to the caller.
+//!
+//! A failed verification is decoded with [`ScriptFailure::parse`].
+//!
+//! ```ignore
+//! let session = VoteSessionBuilder::new(vote_meta)
+//!     .script(code_hash, ScriptHashType::Data1)
+//!     .build(meta_out_point)?;
+//! let tx = BallotBuilder::new(&session, voter_lock)
+//!     .candidate(1)
+//!     .capacity(200_0000_0000)
+//!     .smt_proof(proof)
+//!     .build(tx)?;
+//! ```
+
+mod ballot;
+mod error;
+mod failure;
+mod reclaim;
+mod session;
+
+pub use ballot::BallotBuilder;
+pub use ckb_dao_vote_types::{error::ErrorCode, models::VoteMeta};
+pub use ckb_types;
+pub use error::Error;
+pub use failure::ScriptFailure;
+pub use reclaim::ReclaimBuilder;
+pub use session::{VoteSession, VoteSessionBuilder};
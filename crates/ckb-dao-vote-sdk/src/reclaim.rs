@@ -0,0 +1,105 @@
+use crate::session::VoteSession;
+use ckb_types::{
+    core::{DepType, TransactionView},
+    packed::{Byte32, CellDep, CellInput, OutPoint},
+    prelude::*,
+};
+
+// How a guarded session proves that it has ended
+enum EndProof {
+    // input since of every vote cell set to the session end
+    Since,
+    HeaderDep(Byte32),
+    ResultCellDep(OutPoint),
+    MetaCellConsumed,
+}
+
+/// Consumes vote cells of a session to reclaim their capacity.
+///
+/// Vote cells of a guarded session can only be consumed once the session has
+/// ended. By default the input since of each vote cell is set to the session
+/// end, see `header_dep`, `result_cell_dep` and `consume_meta_cell` for the
+/// other proofs. The outputs receiving the capacity are left to the caller.
+pub struct ReclaimBuilder<'a> {
+    session: &'a VoteSession,
+    vote_cells: Vec<OutPoint>,
+    end_proof: EndProof,
+}
+
+impl<'a> ReclaimBuilder<'a> {
+    pub fn new(session: &'a VoteSession) -> Self {
+        Self {
+            session,
+            vote_cells: Vec::new(),
+            end_proof: EndProof::Since,
+        }
+    }
+
+    pub fn vote_cell(mut self, out_point: OutPoint) -> Self {
+        self.vote_cells.push(out_point);
+        self
+    }
+
+    /// A block header at or after the session end.
+    pub fn header_dep(mut self, block_hash: Byte32) -> Self {
+        self.end_proof = EndProof::HeaderDep(block_hash);
+        self
+    }
+
+    /// A result cell of the session.
+    pub fn result_cell_dep(mut self, out_point: OutPoint) -> Self {
+        self.end_proof = EndProof::ResultCellDep(out_point);
+        self
+    }
+
+    /// The vote meta cell is consumed in the same transaction.
+    pub fn consume_meta_cell(mut self) -> Self {
+        self.end_proof = EndProof::MetaCellConsumed;
+        self
+    }
+
+    pub fn build(self, tx: TransactionView) -> TransactionView {
+        let args = self.session.type_script().args().raw_data();
+        let since = match self.end_proof {
+            EndProof::Since if self.session.is_guarded() => {
+                u64::from_le_bytes(args[20..].try_into().unwrap())
+            }
+            _ => 0,
+        };
+        let mut builder = tx.as_advanced_builder();
+        for out_point in self.vote_cells {
+            builder = builder.input(
+                CellInput::new_builder()
+                    .previous_output(out_point)
+                    .since(since.pack())
+                    .build(),
+            );
+        }
+        match self.end_proof {
+            EndProof::HeaderDep(block_hash) => {
+                builder = builder.header_dep(block_hash);
+            }
+            EndProof::ResultCellDep(out_point) => {
+                builder = builder.cell_dep(
+                    CellDep::new_builder()
+                        .out_point(out_point)
+                        .dep_type(DepType::Code.into())
+                        .build(),
+                );
+            }
+            EndProof::MetaCellConsumed => {
+                // a bonded or policy-closed vote meta cell is only released
+                // at the session end
+                let end_time = self.session.vote_meta().end_time;
+                builder = builder.input(
+                    CellInput::new_builder()
+                        .previous_output(self.session.meta_out_point().clone())
+                        .since(end_time.pack())
+                        .build(),
+                );
+            }
+            EndProof::Since => {}
+        }
+        builder.build()
+    }
+}
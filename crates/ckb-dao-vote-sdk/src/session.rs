@@ -0,0 +1,133 @@
+use crate::error::Error;
+use ckb_dao_vote_types::constants::{META_ARGS, VOTE_ARGS_LEN, VOTE_META_V1};
+use ckb_dao_vote_types::hash::blake160;
+use ckb_dao_vote_types::models::VoteMeta;
+use ckb_types::{
+    bytes::Bytes,
+    core::{Capacity, DepType, ScriptHashType},
+    packed::{Byte32, CellDep, CellOutput, OutPoint, Script},
+    prelude::*,
+};
+
+/// Builds the vote meta cell of a session and derives its scripts.
+pub struct VoteSessionBuilder {
+    vote_meta: VoteMeta,
+    script: Option<(Byte32, ScriptHashType)>,
+}
+
+impl VoteSessionBuilder {
+    pub fn new(vote_meta: VoteMeta) -> Self {
+        Self {
+            vote_meta,
+            script: None,
+        }
+    }
+
+    /// The deployed ckb-dao-vote type script.
+    pub fn script(mut self, code_hash: Byte32, hash_type: ScriptHashType) -> Self {
+        self.script = Some((code_hash, hash_type));
+        self
+    }
+
+    pub fn vote_meta(&self) -> &VoteMeta {
+        &self.vote_meta
+    }
+
+    /// Data of the vote meta cell.
+    pub fn meta_data(&self) -> Result<Bytes, Error> {
+        Ok(serde_molecule::to_vec(&self.vote_meta, false)?.into())
+    }
+
+    /// Sessions with a proposer bond or a closure policy need the vote meta
+    /// type script on the vote meta cell.
+    pub fn requires_meta_type(&self) -> bool {
+        self.vote_meta.proposer_bond.is_some() || self.vote_meta.closure_policy.unwrap_or(0) != 0
+    }
+
+    /// The vote meta cell output and its data. `capacity` must cover the
+    /// proposer bond, if any.
+    pub fn meta_output(
+        &self,
+        lock: Script,
+        capacity: Capacity,
+    ) -> Result<(CellOutput, Bytes), Error> {
+        let mut output = CellOutput::new_builder()
+            .capacity(capacity.pack())
+            .lock(lock);
+        if self.requires_meta_type() {
+            let (code_hash, hash_type) = self.script.clone().ok_or(Error::NoScript)?;
+            let meta_type = Script::new_builder()
+                .code_hash(code_hash)
+                .hash_type(hash_type.into())
+                .args(Bytes::from(META_ARGS.to_vec()).pack())
+                .build();
+            output = output.type_(Some(meta_type).pack());
+        }
+        Ok((output.build(), self.meta_data()?))
+    }
+
+    /// The session of the vote meta cell created at `meta_out_point`.
+    pub fn build(self, meta_out_point: OutPoint) -> Result<VoteSession, Error> {
+        let (code_hash, hash_type) = self.script.ok_or(Error::NoScript)?;
+        let session_id = blake160(meta_out_point.as_slice());
+        let mut args = session_id.to_vec();
+        if self.vote_meta.guarded_consumption.unwrap_or(0) != 0 {
+            args.extend_from_slice(&self.vote_meta.end_time.to_le_bytes());
+        }
+        let type_script = Script::new_builder()
+            .code_hash(code_hash)
+            .hash_type(hash_type.into())
+            .args(Bytes::from(args).pack())
+            .build();
+        Ok(VoteSession {
+            vote_meta: self.vote_meta,
+            meta_out_point,
+            session_id,
+            type_script,
+        })
+    }
+}
+
+/// A vote session: the vote meta cell and the type script of its vote cells.
+pub struct VoteSession {
+    vote_meta: VoteMeta,
+    meta_out_point: OutPoint,
+    session_id: [u8; 20],
+    type_script: Script,
+}
+
+impl VoteSession {
+    pub fn vote_meta(&self) -> &VoteMeta {
+        &self.vote_meta
+    }
+
+    pub fn version(&self) -> u8 {
+        self.vote_meta.version.unwrap_or(VOTE_META_V1)
+    }
+
+    /// blake160 of the vote meta cell out point
+    pub fn session_id(&self) -> [u8; 20] {
+        self.session_id
+    }
+
+    pub fn meta_out_point(&self) -> &OutPoint {
+        &self.meta_out_point
+    }
+
+    /// Type script of the vote cells.
+    pub fn type_script(&self) -> &Script {
+        &self.type_script
+    }
+
+    pub fn is_guarded(&self) -> bool {
+        self.type_script.args().raw_data().len() > VOTE_ARGS_LEN
+    }
+
+    /// Vote creations must reference the vote meta cell.
+    pub fn meta_cell_dep(&self) -> CellDep {
+        CellDep::new_builder()
+            .out_point(self.meta_out_point.clone())
+            .dep_type(DepType::Code.into())
+            .build()
+    }
+}
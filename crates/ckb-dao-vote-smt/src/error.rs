@@ -0,0 +1,23 @@
+use core::fmt::Display;
+
+#[derive(Debug)]
+pub enum Error {
+    // a line or entry of the voter list can't be parsed
+    Parse(String),
+    DuplicateVoter([u8; 32]),
+    UnknownVoter([u8; 32]),
+    Smt(sparse_merkle_tree::error::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Parse(reason) => write!(f, "invalid voter list: {}", reason),
+            Error::DuplicateVoter(hash) => write!(f, "duplicate voter 0x{}", hex::encode(hash)),
+            Error::UnknownVoter(hash) => write!(f, "unknown voter 0x{}", hex::encode(hash)),
+            Error::Smt(e) => write!(f, "SMT error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,123 @@
+use crate::error::Error;
+use ckb_types::{bytes::Bytes, core::ScriptHashType, packed::Script, prelude::*};
+use serde::Deserialize;
+
+/// A voter of the eligibility list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Voter {
+    pub lock_hash: [u8; 32],
+    pub weight: Option<u64>,
+}
+
+fn parse_hex(value: &str) -> Result<Vec<u8>, Error> {
+    let value = value.trim();
+    hex::decode(value.strip_prefix("0x").unwrap_or(value))
+        .map_err(|e| Error::Parse(format!("{}: {}", value, e)))
+}
+
+fn parse_hash(value: &str) -> Result<[u8; 32], Error> {
+    parse_hex(value)?
+        .try_into()
+        .map_err(|_| Error::Parse(format!("{}: not a 32-byte hash", value.trim())))
+}
+
+fn parse_hash_type(value: &str) -> Result<ScriptHashType, Error> {
+    match value.trim() {
+        "data" => Ok(ScriptHashType::Data),
+        "type" => Ok(ScriptHashType::Type),
+        "data1" => Ok(ScriptHashType::Data1),
+        "data2" => Ok(ScriptHashType::Data2),
+        other => Err(Error::Parse(format!("unknown hash type {}", other))),
+    }
+}
+
+fn parse_weight(value: &str) -> Result<u64, Error> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| Error::Parse(format!("invalid weight {}", value.trim())))
+}
+
+fn lock_hash(code_hash: &str, hash_type: &str, args: &str) -> Result<[u8; 32], Error> {
+    let script = Script::new_builder()
+        .code_hash(parse_hash(code_hash)?.pack())
+        .hash_type(parse_hash_type(hash_type)?.into())
+        .args(Bytes::from(parse_hex(args)?).pack())
+        .build();
+    Ok(script.calc_script_hash().unpack().0)
+}
+
+/// One voter per line, either `lock_hash[,weight]` or
+/// `code_hash,hash_type,args[,weight]`. Empty lines and lines starting with
+/// `#` are skipped.
+pub fn parse_csv(text: &str) -> Result<Vec<Voter>, Error> {
+    let mut voters = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').collect();
+        let voter = match fields[..] {
+            [hash] => Voter {
+                lock_hash: parse_hash(hash)?,
+                weight: None,
+            },
+            [hash, weight] => Voter {
+                lock_hash: parse_hash(hash)?,
+                weight: Some(parse_weight(weight)?),
+            },
+            [code_hash, hash_type, args] => Voter {
+                lock_hash: lock_hash(code_hash, hash_type, args)?,
+                weight: None,
+            },
+            [code_hash, hash_type, args, weight] => Voter {
+                lock_hash: lock_hash(code_hash, hash_type, args)?,
+                weight: Some(parse_weight(weight)?),
+            },
+            _ => return Err(Error::Parse(format!("unexpected line {}", line))),
+        };
+        voters.push(voter);
+    }
+    Ok(voters)
+}
+
+#[derive(Deserialize)]
+struct JsonScript {
+    code_hash: String,
+    hash_type: String,
+    args: String,
+}
+
+#[derive(Deserialize)]
+struct JsonVoter {
+    lock_hash: Option<String>,
+    lock: Option<JsonScript>,
+    weight: Option<u64>,
+}
+
+/// An array of `{"lock_hash": "0x..."}` or
+/// `{"lock": {"code_hash": "0x...", "hash_type": "type", "args": "0x..."}}`
+/// entries, each with an optional `"weight"`.
+pub fn parse_json(text: &str) -> Result<Vec<Voter>, Error> {
+    let entries: Vec<JsonVoter> =
+        serde_json::from_str(text).map_err(|e| Error::Parse(e.to_string()))?;
+    entries
+        .into_iter()
+        .map(|entry| {
+            let lock_hash = match (entry.lock_hash, entry.lock) {
+                (Some(hash), None) => parse_hash(&hash)?,
+                (None, Some(lock)) => lock_hash(&lock.code_hash, &lock.hash_type, &lock.args)?,
+                _ => {
+                    return Err(Error::Parse(
+                        "expected exactly one of lock_hash and lock".to_string(),
+                    ))
+                }
+            };
+            Ok(Voter {
+                lock_hash,
+                weight: entry.weight,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,71 @@
+//! Eligibility SMT of a vote session: the tree whose root is `smt_root_hash`
+//! and the compiled proofs voters put in `VoteProof.smt_proof`.
+
+mod error;
+mod input;
+
+pub use error::Error;
+pub use input::{parse_csv, parse_json, Voter};
+
+use ckb_dao_vote_types::{constants::SMT_VALUE, smt_hasher::Blake2bHasher};
+use sparse_merkle_tree::{default_store::DefaultStore, SparseMerkleTree, H256};
+use std::collections::BTreeMap;
+
+pub type CkbSMT = SparseMerkleTree<Blake2bHasher, H256, DefaultStore<H256>>;
+
+/// Leaves are keyed by the voter's lock script hash and all hold
+/// `SMT_VALUE`, the same encoding the type script verifies. Weights are not
+/// part of the tree, they only add up to `eligible_weight`, and the type
+/// script does not enforce them.
+pub struct EligibilityTree {
+    smt: CkbSMT,
+    voters: BTreeMap<[u8; 32], u64>,
+}
+
+impl EligibilityTree {
+    pub fn new(voters: &[Voter]) -> Result<Self, Error> {
+        let mut smt = CkbSMT::default();
+        let mut weights = BTreeMap::new();
+        for voter in voters {
+            if weights
+                .insert(voter.lock_hash, voter.weight.unwrap_or(1))
+                .is_some()
+            {
+                return Err(Error::DuplicateVoter(voter.lock_hash));
+            }
+            smt.update(voter.lock_hash.into(), SMT_VALUE.into())
+                .map_err(Error::Smt)?;
+        }
+        Ok(Self {
+            smt,
+            voters: weights,
+        })
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        (*self.smt.root()).into()
+    }
+
+    /// Sum of the voters' weights, a voter without weight counts as 1.
+    pub fn eligible_weight(&self) -> u64 {
+        self.voters.values().sum()
+    }
+
+    pub fn voters(&self) -> impl Iterator<Item = &[u8; 32]> {
+        self.voters.keys()
+    }
+
+    /// Compiled proof of `lock_hash`, for `VoteProof.smt_proof`.
+    pub fn proof(&self, lock_hash: &[u8; 32]) -> Result<Vec<u8>, Error> {
+        if !self.voters.contains_key(lock_hash) {
+            return Err(Error::UnknownVoter(*lock_hash));
+        }
+        let key: H256 = (*lock_hash).into();
+        let proof = self
+            .smt
+            .merkle_proof(vec![key])
+            .and_then(|proof| proof.compile(vec![key]))
+            .map_err(Error::Smt)?;
+        Ok(proof.0)
+    }
+}
@@ -0,0 +1,110 @@
+use ckb_dao_vote_smt::{parse_csv, parse_json, EligibilityTree};
+use ckb_dao_vote_types::models::VoteMeta;
+use std::path::PathBuf;
+use std::process::exit;
+
+const USAGE: &str = "\
+Usage: ckb-dao-vote-smt <VOTERS> --out <DIR> [--candidates <A,B,...>] [--start-time <N>] [--end-time <N>]
+
+Builds the eligibility SMT of a vote session from a voter list.
+
+VOTERS is a JSON file (*.json) or a CSV file, see the crate README for the format.
+Writes <DIR>/vote_meta.bin and one compiled proof per voter at
+<DIR>/proofs/<lock hash>.bin, and prints the root as JSON.";
+
+struct Options {
+    voters: PathBuf,
+    out: PathBuf,
+    candidates: Vec<Vec<u8>>,
+    start_time: u64,
+    end_time: u64,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut args = std::env::args().skip(1);
+    let mut voters = None;
+    let mut out = None;
+    let mut candidates = Vec::new();
+    let mut start_time = 0;
+    let mut end_time = 0;
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value of {}", arg));
+        match arg.as_str() {
+            "--out" => out = Some(PathBuf::from(value()?)),
+            "--candidates" => {
+                candidates = value()?.split(',').map(|c| c.as_bytes().to_vec()).collect()
+            }
+            "--start-time" => start_time = value()?.parse().map_err(|_| "invalid start time")?,
+            "--end-time" => end_time = value()?.parse().map_err(|_| "invalid end time")?,
+            "-h" | "--help" => return Err(String::new()),
+            _ if voters.is_none() && !arg.starts_with('-') => voters = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+    Ok(Options {
+        voters: voters.ok_or("missing voter list")?,
+        out: out.ok_or("missing --out")?,
+        candidates,
+        start_time,
+        end_time,
+    })
+}
+
+fn run(options: Options) -> Result<(), Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(&options.voters)?;
+    let voters = match options.voters.extension().and_then(|e| e.to_str()) {
+        Some("json") => parse_json(&text)?,
+        _ => parse_csv(&text)?,
+    };
+    let tree = EligibilityTree::new(&voters)?;
+
+    let vote_meta = VoteMeta {
+        smt_root_hash: Some(tree.root()),
+        candidates: options.candidates,
+        start_time: options.start_time,
+        end_time: options.end_time,
+        // only meaningful for weighted lists
+        eligible_weight: voters
+            .iter()
+            .any(|v| v.weight.is_some())
+            .then(|| tree.eligible_weight()),
+        ..Default::default()
+    };
+    let proofs = options.out.join("proofs");
+    std::fs::create_dir_all(&proofs)?;
+    std::fs::write(
+        options.out.join("vote_meta.bin"),
+        serde_molecule::to_vec(&vote_meta, false)?,
+    )?;
+    for lock_hash in tree.voters() {
+        std::fs::write(
+            proofs.join(format!("{}.bin", hex::encode(lock_hash))),
+            tree.proof(lock_hash)?,
+        )?;
+    }
+
+    let summary = serde_json::json!({
+        "smt_root_hash": format!("0x{}", hex::encode(tree.root())),
+        "voters": voters.len(),
+        "eligible_weight": vote_meta.eligible_weight,
+    });
+    println!("{}", serde_json::to_string_pretty(&summary)?);
+    Ok(())
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(reason) => {
+            if !reason.is_empty() {
+                eprintln!("error: {}\n", reason);
+            }
+            eprintln!("{}", USAGE);
+            exit(2);
+        }
+    };
+    if let Err(e) = run(options) {
+        eprintln!("error: {}", e);
+        exit(1);
+    }
+}
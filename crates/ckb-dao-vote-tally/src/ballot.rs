@@ -0,0 +1,153 @@
+use crate::rules::Rules;
+use crate::since::{is_absolute_since, relative_epochs};
+use crate::VoteCell;
+use ckb_dao_vote_types::constants::{
+    BITMAP_LEN, ESCROW_LOCK_ARGS_LEN, GUARDED_ARGS_LEN, SMT_VALUE, VOTE_ARGS_LEN, VOTE_META_V1,
+    WEIGHT_MODE_BALLOT, WEIGHT_MODE_CAPACITY, WEIGHT_MODE_ESCROW,
+};
+use ckb_dao_vote_types::error::ErrorCode;
+use ckb_dao_vote_types::molecules::vote::VoteProof;
+use ckb_dao_vote_types::smt_hasher::Blake2bHasher;
+use ckb_types::{packed::WitnessArgs, prelude::*};
+use molecule::lazy_reader::Cursor;
+use sparse_merkle_tree::CompiledMerkleProof;
+
+/// A ballot that passed the type script rules.
+pub(crate) struct Ballot {
+    pub lock_hash: [u8; 32],
+    pub choices: u32,
+    pub weight: u64,
+}
+
+fn vote_proof(cell: &VoteCell) -> Result<([u8; 32], Vec<u8>), ErrorCode> {
+    if cell.witness.is_empty() {
+        return Err(ErrorCode::MissingWitness);
+    }
+    let witness_args =
+        WitnessArgs::from_slice(&cell.witness).map_err(|_| ErrorCode::CorruptWitness)?;
+    let output_type = witness_args
+        .output_type()
+        .to_opt()
+        .ok_or(ErrorCode::CorruptWitness)?;
+    let vote_proof = VoteProof::from(Cursor::from(output_type.raw_data().to_vec()));
+    let read = || -> Result<_, molecule::lazy_reader::Error> {
+        vote_proof.verify(false)?;
+        Ok((
+            vote_proof.lock_script_hash()?,
+            vote_proof.smt_proof()?.try_into()?,
+        ))
+    };
+    read().map_err(|_| ErrorCode::CorruptWitness)
+}
+
+fn escrow_weight(rules: &Rules, cell: &VoteCell, capacity: u64) -> Result<u64, ErrorCode> {
+    let code_hash = rules
+        .escrow_lock_code_hash
+        .ok_or(ErrorCode::WrongWeightMode)?;
+    let hash_type = rules
+        .escrow_lock_hash_type
+        .ok_or(ErrorCode::WrongWeightMode)?;
+    if rules.escrow_min_epochs == 0 || rules.escrow_max_epochs < rules.escrow_min_epochs {
+        return Err(ErrorCode::WrongWeightMode);
+    }
+    let lock = cell.output.lock();
+    let args = lock.args().raw_data();
+    if lock.code_hash().as_slice() != code_hash
+        || lock.hash_type().as_slice() != [hash_type]
+        || args.len() != ESCROW_LOCK_ARGS_LEN
+    {
+        return Err(ErrorCode::WrongBallotWeight);
+    }
+    let since = u64::from_le_bytes(args[20..].try_into().unwrap());
+    // durations in units of 1/length epoch, as in the type script
+    let (number, index, length) = relative_epochs(since).ok_or(ErrorCode::WrongBallotWeight)?;
+    let length = u128::from(length);
+    let locked = u128::from(number) * length + u128::from(index);
+    let min_locked = u128::from(rules.escrow_min_epochs) * length;
+    if locked < min_locked {
+        return Err(ErrorCode::WrongBallotWeight);
+    }
+    let locked = locked.min(u128::from(rules.escrow_max_epochs) * length);
+    let weight = u128::from(capacity) * locked / min_locked;
+    u64::try_from(weight).map_err(|_| ErrorCode::WrongBallotWeight)
+}
+
+/// Applies the checks of the type script on vote creation to a single vote
+/// cell. Deposit snapshot eligibility needs the cell deps of the creating
+/// transaction and is not checked here.
+pub(crate) fn validate(rules: &Rules, cell: &VoteCell) -> Result<Ballot, ErrorCode> {
+    let args = cell
+        .output
+        .type_()
+        .to_opt()
+        .ok_or(ErrorCode::WrongArgs)?
+        .args()
+        .raw_data();
+    if rules.guarded {
+        if !is_absolute_since(rules.end_time)
+            || args.len() != GUARDED_ARGS_LEN
+            || args[VOTE_ARGS_LEN..] != rules.end_time.to_le_bytes()
+        {
+            return Err(ErrorCode::WrongArgs);
+        }
+    } else if args.len() != VOTE_ARGS_LEN {
+        return Err(ErrorCode::WrongArgs);
+    }
+    if rules.weight_mode == WEIGHT_MODE_ESCROW && rules.version == VOTE_META_V1 {
+        return Err(ErrorCode::WrongWeightMode);
+    }
+    let (lock_hash, smt_proof) = vote_proof(cell)?;
+    if let Some(root) = rules.smt_root_hash {
+        let verified = CompiledMerkleProof(smt_proof)
+            .verify::<Blake2bHasher>(&root.into(), vec![(lock_hash.into(), SMT_VALUE.into())])
+            .unwrap_or(false);
+        if !verified {
+            return Err(ErrorCode::VerifySmtFail);
+        }
+    }
+    if let Some(input_lock_hashes) = &cell.input_lock_hashes {
+        if !input_lock_hashes.contains(&lock_hash) {
+            return Err(ErrorCode::NoLockFound);
+        }
+    }
+
+    let ballot_len = match rules.version {
+        VOTE_META_V1 => BITMAP_LEN,
+        _ => BITMAP_LEN + 8,
+    };
+    if cell.data.len() != ballot_len {
+        return Err(ErrorCode::WrongBallotLength);
+    }
+    let choices = u32::from_le_bytes(cell.data[..BITMAP_LEN].try_into().unwrap());
+    // an empty bitmap is an abstain ballot
+    if choices == 0 && !rules.allow_abstain {
+        return Err(ErrorCode::AbstainNotAllowed);
+    }
+    if rules.candidates_size > 32 {
+        return Err(ErrorCode::TooManyCandidates);
+    }
+    let highest_bit = 32 - choices.leading_zeros() as usize;
+    if highest_bit > rules.candidates_size {
+        return Err(ErrorCode::WrongVoteCandidate);
+    }
+    let capacity: u64 = cell.output.capacity().unpack();
+    if rules.min_vote_capacity.is_some_and(|min| capacity < min)
+        || rules.max_vote_capacity.is_some_and(|max| capacity > max)
+    {
+        return Err(ErrorCode::WrongVoteCapacity);
+    }
+    let weight = match rules.weight_mode {
+        WEIGHT_MODE_BALLOT => 1,
+        WEIGHT_MODE_CAPACITY => capacity,
+        WEIGHT_MODE_ESCROW => escrow_weight(rules, cell, capacity)?,
+        _ => return Err(ErrorCode::WrongWeightMode),
+    };
+    if rules.version != VOTE_META_V1 && cell.data[BITMAP_LEN..] != weight.to_le_bytes() {
+        return Err(ErrorCode::WrongBallotWeight);
+    }
+    Ok(Ballot {
+        lock_hash,
+        choices,
+        weight,
+    })
+}
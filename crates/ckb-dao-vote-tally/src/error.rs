@@ -0,0 +1,35 @@
+use ckb_dao_vote_types::error::ErrorCode;
+use core::fmt::Display;
+
+#[derive(Debug)]
+pub enum Error {
+    Json(serde_json::Error),
+    // the vote meta cell is not a valid `VoteMeta`
+    VoteMeta(ErrorCode),
+    // no transaction has the vote meta cell in its cell deps
+    NoMetaCell,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Json(e) => write!(f, "invalid JSON: {}", e),
+            Error::VoteMeta(code) => write!(f, "invalid vote meta cell: {}", code.description()),
+            Error::NoMetaCell => write!(f, "vote meta cell not found in cell deps"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+impl From<ErrorCode> for Error {
+    fn from(code: ErrorCode) -> Self {
+        Error::VoteMeta(code)
+    }
+}
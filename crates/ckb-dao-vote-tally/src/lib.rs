@@ -0,0 +1,235 @@
+//! Off-chain tally of a ckb-dao-vote session.
+//!
+//! Ballots are validated with the rules of the type script, limited to the
+//! vote time window, deduplicated per voter and counted in the same way as a
+//! tally cell. The result follows the quorum and pass threshold rules of
+//! finalization.
+
+mod ballot;
+mod error;
+mod mock;
+mod rules;
+mod since;
+
+pub use error::Error;
+pub use mock::{load_block_transactions, BlockTransaction};
+pub use since::{since_reached, BlockInfo};
+
+use ckb_dao_vote_types::constants::BPS;
+use ckb_dao_vote_types::error::ErrorCode;
+use ckb_types::{
+    bytes::Bytes,
+    core::ScriptHashType,
+    packed::{Byte32, CellOutput, OutPoint},
+    prelude::*,
+};
+use rules::Rules;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A vote cell as created on chain.
+#[derive(Clone, Debug)]
+pub struct VoteCell {
+    pub out_point: OutPoint,
+    pub output: CellOutput,
+    pub data: Bytes,
+    /// witness at the vote cell's index in the creating transaction
+    pub witness: Bytes,
+    /// lock script hashes of the creating transaction's inputs, when known
+    pub input_lock_hashes: Option<Vec<[u8; 32]>>,
+    pub block: BlockInfo,
+}
+
+/// Which ballot counts when a voter voted more than once.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DedupPolicy {
+    #[default]
+    LatestWins,
+    FirstWins,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct Rejection {
+    /// `<tx hash>:<index>` of the vote cell
+    pub out_point: String,
+    pub code: i8,
+    pub reason: &'static str,
+}
+
+#[derive(Clone, Debug, Default, Serialize, PartialEq, Eq)]
+pub struct TallyReport {
+    pub counts: Vec<u64>,
+    pub turnout: u64,
+    pub abstain: u64,
+    /// candidates with the highest count, in the bit layout of a ballot
+    pub winners: u32,
+    pub quorum_reached: bool,
+    pub passed: bool,
+    /// number of counted ballots
+    pub ballots: usize,
+    /// valid ballots replaced by another ballot of the same voter
+    pub superseded: usize,
+    /// valid ballots committed outside of the vote time window
+    pub outside_window: usize,
+    pub rejected: Vec<Rejection>,
+}
+
+pub struct TallyEngine {
+    rules: Rules,
+    session_id: [u8; 20],
+    code_hash: Byte32,
+    hash_type: ScriptHashType,
+    policy: DedupPolicy,
+}
+
+fn out_point_string(out_point: &OutPoint) -> String {
+    let index: u32 = out_point.index().unpack();
+    format!(
+        "0x{}:{}",
+        hex::encode(out_point.tx_hash().as_slice()),
+        index
+    )
+}
+
+impl TallyEngine {
+    /// `meta_data` is the data of the vote meta cell, `session_id` the blake160
+    /// of its out point, `code_hash` and `hash_type` those of the deployed
+    /// ckb-dao-vote type script.
+    pub fn new(
+        meta_data: &[u8],
+        session_id: [u8; 20],
+        code_hash: Byte32,
+        hash_type: ScriptHashType,
+        policy: DedupPolicy,
+    ) -> Result<Self, ErrorCode> {
+        Ok(Self {
+            rules: Rules::new(meta_data)?,
+            session_id,
+            code_hash,
+            hash_type,
+            policy,
+        })
+    }
+
+    /// Whether a cell is a vote cell of this session: typed by the vote type
+    /// script, with args that start with the session id.
+    pub fn is_session_cell(&self, output: &CellOutput) -> bool {
+        output.type_().to_opt().is_some_and(|script| {
+            let args = script.args().raw_data();
+            script.code_hash() == self.code_hash
+                && script.hash_type() == self.hash_type.into()
+                && args.len() >= self.session_id.len()
+                && args[..self.session_id.len()] == self.session_id
+        })
+    }
+
+    // `end_time` of 0 leaves the window open, as in unguarded test sessions.
+    fn in_window(&self, block: &BlockInfo) -> bool {
+        since_reached(block, self.rules.start_time)
+            && (self.rules.end_time == 0 || !since_reached(block, self.rules.end_time))
+    }
+
+    pub fn tally(&self, cells: &[VoteCell]) -> TallyReport {
+        let mut report = TallyReport {
+            counts: vec![0; self.rules.candidates_size],
+            ..Default::default()
+        };
+        let mut cells: Vec<&VoteCell> = cells
+            .iter()
+            .filter(|cell| self.is_session_cell(&cell.output))
+            .collect();
+        cells.sort_by_key(|cell| {
+            let index: u32 = cell.out_point.index().unpack();
+            (cell.block.number, cell.block.tx_index, index)
+        });
+
+        let mut ballots = BTreeMap::new();
+        for cell in cells {
+            let ballot = match ballot::validate(&self.rules, cell) {
+                Ok(ballot) => ballot,
+                Err(code) => {
+                    report.rejected.push(Rejection {
+                        out_point: out_point_string(&cell.out_point),
+                        code: code.code(),
+                        reason: code.description(),
+                    });
+                    continue;
+                }
+            };
+            if !self.in_window(&cell.block) {
+                report.outside_window += 1;
+                continue;
+            }
+            let replaced = ballots.contains_key(&ballot.lock_hash);
+            if replaced {
+                report.superseded += 1;
+            }
+            if !replaced || self.policy == DedupPolicy::LatestWins {
+                ballots.insert(ballot.lock_hash, (ballot.choices, ballot.weight));
+            }
+        }
+
+        for (choices, weight) in ballots.values() {
+            if *choices == 0 {
+                report.abstain = report.abstain.saturating_add(*weight);
+            }
+            for (bit, count) in report.counts.iter_mut().enumerate() {
+                if choices & (1 << bit) != 0 {
+                    *count = count.saturating_add(*weight);
+                }
+            }
+            report.turnout = report.turnout.saturating_add(*weight);
+        }
+        report.ballots = ballots.len();
+        report.winners = winners(&report.counts);
+        report.quorum_reached = self.quorum_reached(report.turnout);
+        report.passed = report.quorum_reached
+            && self.threshold_met(
+                report.winners,
+                &report.counts,
+                report.turnout - report.abstain,
+            );
+        report
+    }
+
+    fn quorum_reached(&self, turnout: u64) -> bool {
+        if self.rules.quorum.is_some_and(|quorum| turnout < quorum) {
+            return false;
+        }
+        if let Some(quorum_bps) = self.rules.quorum_bps {
+            let Some(eligible_weight) = self.rules.eligible_weight else {
+                return false;
+            };
+            if u128::from(turnout) * BPS < u128::from(quorum_bps) * u128::from(eligible_weight) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn threshold_met(&self, winners: u32, counts: &[u64], support: u64) -> bool {
+        if winners.count_ones() != 1 {
+            return false;
+        }
+        let count = counts[winners.trailing_zeros() as usize];
+        match self.rules.pass_threshold_bps {
+            Some(threshold) => {
+                u128::from(count) * BPS > u128::from(threshold) * u128::from(support)
+            }
+            None => true,
+        }
+    }
+}
+
+// Candidates with the highest count, in the same bit layout as a ballot.
+fn winners(counts: &[u64]) -> u32 {
+    let max = counts.iter().copied().max().unwrap_or(0);
+    if max == 0 {
+        return 0;
+    }
+    counts
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count == max)
+        .fold(0, |winners, (index, _)| winners | (1 << index))
+}
@@ -0,0 +1,129 @@
+use ckb_dao_vote_tally::{load_block_transactions, DedupPolicy, Error, TallyEngine};
+use ckb_types::{core::ScriptHashType, packed::Byte32, prelude::*};
+use std::path::PathBuf;
+use std::process::exit;
+
+const USAGE: &str = "\
+Usage: ckb-dao-vote-tally --session <SESSION_ID> --code-hash <CODE_HASH> [--hash-type data|type|data1|data2] [--policy latest|first] [--meta <FILE>] <TRANSACTIONS>
+
+Tallies the vote cells of a session and prints the result as JSON.
+
+TRANSACTIONS is a JSON array of {block_number, timestamp, epoch, tx_index, tx},
+where tx is a mock transaction as dumped by verify_and_dump_failed_tx.
+SESSION_ID is the hex encoded type script args of the vote cells, without the
+guarded end time. CODE_HASH and the hash type (default: type) identify the vote
+type script. The vote meta cell data is read from FILE, or otherwise from the
+cell deps of the transactions.";
+
+struct Options {
+    transactions: PathBuf,
+    session_id: [u8; 20],
+    code_hash: Byte32,
+    hash_type: ScriptHashType,
+    policy: DedupPolicy,
+    meta: Option<PathBuf>,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut args = std::env::args().skip(1);
+    let mut transactions = None;
+    let mut session_id = None;
+    let mut code_hash = None;
+    let mut hash_type = ScriptHashType::Type;
+    let mut policy = DedupPolicy::default();
+    let mut meta = None;
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value of {}", arg));
+        match arg.as_str() {
+            "--session" => {
+                let value = value()?;
+                let bytes = hex::decode(value.trim_start_matches("0x"))
+                    .map_err(|_| "invalid session id")?;
+                session_id = Some(
+                    bytes
+                        .try_into()
+                        .map_err(|_| "session id must be 20 bytes")?,
+                );
+            }
+            "--code-hash" => {
+                let value = value()?;
+                let bytes: [u8; 32] = hex::decode(value.trim_start_matches("0x"))
+                    .map_err(|_| "invalid code hash")?
+                    .try_into()
+                    .map_err(|_| "code hash must be 32 bytes")?;
+                code_hash = Some(bytes.pack());
+            }
+            "--hash-type" => {
+                hash_type = match value()?.as_str() {
+                    "data" => ScriptHashType::Data,
+                    "type" => ScriptHashType::Type,
+                    "data1" => ScriptHashType::Data1,
+                    "data2" => ScriptHashType::Data2,
+                    other => return Err(format!("unknown hash type {}", other)),
+                }
+            }
+            "--policy" => {
+                policy = match value()?.as_str() {
+                    "latest" => DedupPolicy::LatestWins,
+                    "first" => DedupPolicy::FirstWins,
+                    other => return Err(format!("unknown policy {}", other)),
+                }
+            }
+            "--meta" => meta = Some(PathBuf::from(value()?)),
+            "-h" | "--help" => return Err(String::new()),
+            _ if transactions.is_none() && !arg.starts_with('-') => {
+                transactions = Some(PathBuf::from(arg))
+            }
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+    Ok(Options {
+        transactions: transactions.ok_or("missing transactions")?,
+        session_id: session_id.ok_or("missing --session")?,
+        code_hash: code_hash.ok_or("missing --code-hash")?,
+        hash_type,
+        policy,
+        meta,
+    })
+}
+
+fn run(options: Options) -> Result<(), Box<dyn std::error::Error>> {
+    let transactions = load_block_transactions(&std::fs::read_to_string(&options.transactions)?)?;
+    let meta_data = match &options.meta {
+        Some(path) => std::fs::read(path)?,
+        None => transactions
+            .iter()
+            .find_map(|tx| tx.meta_cell_data(&options.session_id))
+            .ok_or(Error::NoMetaCell)?
+            .to_vec(),
+    };
+    let engine = TallyEngine::new(
+        &meta_data,
+        options.session_id,
+        options.code_hash,
+        options.hash_type,
+        options.policy,
+    )
+    .map_err(Error::from)?;
+    let cells: Vec<_> = transactions.iter().flat_map(|tx| tx.vote_cells()).collect();
+    let report = engine.tally(&cells);
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(reason) => {
+            if !reason.is_empty() {
+                eprintln!("error: {}\n", reason);
+            }
+            eprintln!("{}", USAGE);
+            exit(2);
+        }
+    };
+    if let Err(e) = run(options) {
+        eprintln!("error: {}", e);
+        exit(1);
+    }
+}
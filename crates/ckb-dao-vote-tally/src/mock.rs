@@ -0,0 +1,81 @@
+use crate::error::Error;
+use crate::{BlockInfo, VoteCell};
+use ckb_dao_vote_types::hash::blake160;
+use ckb_mock_tx_types::{MockTransaction, ReprMockTransaction};
+use ckb_types::{bytes::Bytes, packed::OutPoint, prelude::*};
+use serde::Deserialize;
+
+/// A transaction in the mock tx format dumped by `verify_and_dump_failed_tx`,
+/// with the block that committed it.
+#[derive(Deserialize)]
+pub struct BlockTransaction {
+    pub block_number: u64,
+    /// block timestamp in milliseconds
+    #[serde(default)]
+    pub timestamp: u64,
+    /// full value of the block's EpochNumberWithFraction
+    #[serde(default)]
+    pub epoch: u64,
+    /// position of the transaction in the block
+    #[serde(default)]
+    pub tx_index: usize,
+    pub tx: ReprMockTransaction,
+}
+
+/// Parses a JSON array of `BlockTransaction`.
+pub fn load_block_transactions(json: &str) -> Result<Vec<BlockTransaction>, Error> {
+    Ok(serde_json::from_str(json)?)
+}
+
+impl BlockTransaction {
+    fn block(&self) -> BlockInfo {
+        BlockInfo {
+            number: self.block_number,
+            timestamp: self.timestamp,
+            epoch: self.epoch,
+            tx_index: self.tx_index,
+        }
+    }
+
+    /// All typed outputs of the transaction, with their witnesses.
+    pub fn vote_cells(&self) -> Vec<VoteCell> {
+        let mock: MockTransaction = self.tx.clone().into();
+        let tx_hash = mock.tx.calc_tx_hash();
+        let input_lock_hashes: Vec<[u8; 32]> = mock
+            .mock_info
+            .inputs
+            .iter()
+            .map(|input| input.output.lock().calc_script_hash().unpack().0)
+            .collect();
+        let raw = mock.tx.raw();
+        raw.outputs()
+            .into_iter()
+            .zip(raw.outputs_data())
+            .enumerate()
+            .filter(|(_, (output, _))| output.type_().is_some())
+            .map(|(index, (output, data))| VoteCell {
+                out_point: OutPoint::new(tx_hash.clone(), index as u32),
+                output,
+                data: data.raw_data(),
+                witness: mock
+                    .tx
+                    .witnesses()
+                    .get(index)
+                    .map(|witness| witness.raw_data())
+                    .unwrap_or_default(),
+                input_lock_hashes: Some(input_lock_hashes.clone()),
+                block: self.block(),
+            })
+            .collect()
+    }
+
+    /// Data of the vote meta cell of `session_id`, when it is a cell dep.
+    pub fn meta_cell_data(&self, session_id: &[u8; 20]) -> Option<Bytes> {
+        let mock: MockTransaction = self.tx.clone().into();
+        mock.mock_info
+            .cell_deps
+            .into_iter()
+            .find(|dep| blake160(dep.cell_dep.out_point().as_slice()) == *session_id)
+            .map(|dep| dep.data)
+    }
+}
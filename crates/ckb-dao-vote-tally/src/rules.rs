@@ -0,0 +1,66 @@
+use ckb_dao_vote_types::constants::{VOTE_META_V1, VOTE_META_V2, WEIGHT_MODE_BALLOT};
+use ckb_dao_vote_types::error::ErrorCode;
+use ckb_dao_vote_types::molecules::vote::VoteMeta;
+use molecule::lazy_reader::{Cursor, Error as MoleculeError};
+
+/// The fields of a vote meta cell that decide how ballots are counted.
+pub(crate) struct Rules {
+    pub smt_root_hash: Option<[u8; 32]>,
+    pub candidates_size: usize,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub min_vote_capacity: Option<u64>,
+    pub max_vote_capacity: Option<u64>,
+    pub guarded: bool,
+    pub weight_mode: u8,
+    pub eligible_weight: Option<u64>,
+    pub quorum: Option<u64>,
+    pub quorum_bps: Option<u32>,
+    pub pass_threshold_bps: Option<u32>,
+    pub allow_abstain: bool,
+    pub version: u8,
+    pub escrow_lock_code_hash: Option<[u8; 32]>,
+    pub escrow_min_epochs: u64,
+    pub escrow_max_epochs: u64,
+    pub escrow_lock_hash_type: Option<u8>,
+}
+
+fn read(meta_data: &[u8]) -> Result<Rules, MoleculeError> {
+    let vote_meta = VoteMeta::from(Cursor::from(meta_data.to_vec()));
+    vote_meta.verify_compatible()?;
+    Ok(Rules {
+        smt_root_hash: vote_meta.smt_root_hash()?,
+        candidates_size: vote_meta.candidates()?.len()?,
+        start_time: vote_meta.start_time()?,
+        end_time: vote_meta.end_time()?,
+        min_vote_capacity: vote_meta.min_vote_capacity_opt()?,
+        max_vote_capacity: vote_meta.max_vote_capacity_opt()?,
+        guarded: vote_meta.guarded_consumption_opt()?.unwrap_or(0) != 0,
+        weight_mode: vote_meta.weight_mode_opt()?.unwrap_or(WEIGHT_MODE_BALLOT),
+        eligible_weight: vote_meta.eligible_weight_opt()?,
+        quorum: vote_meta.quorum_opt()?,
+        quorum_bps: vote_meta.quorum_bps_opt()?,
+        pass_threshold_bps: vote_meta.pass_threshold_bps_opt()?,
+        allow_abstain: vote_meta.allow_abstain_opt()?.unwrap_or(0) != 0,
+        version: vote_meta.version_opt()?.unwrap_or(VOTE_META_V1),
+        escrow_lock_code_hash: vote_meta.escrow_lock_code_hash_opt()?,
+        escrow_min_epochs: vote_meta.escrow_min_epochs_opt()?.unwrap_or(0),
+        escrow_max_epochs: vote_meta.escrow_max_epochs_opt()?.unwrap_or(u64::MAX),
+        escrow_lock_hash_type: vote_meta.escrow_lock_hash_type_opt()?,
+    })
+}
+
+impl Rules {
+    pub fn new(meta_data: &[u8]) -> Result<Self, ErrorCode> {
+        let rules = read(meta_data).map_err(|_| ErrorCode::CorruptMeta)?;
+        if rules.version != VOTE_META_V1 && rules.version != VOTE_META_V2 {
+            return Err(ErrorCode::UnsupportedVersion);
+        }
+        if let (Some(min), Some(max)) = (rules.min_vote_capacity, rules.max_vote_capacity) {
+            if min > max {
+                return Err(ErrorCode::WrongVoteCapacity);
+            }
+        }
+        Ok(rules)
+    }
+}
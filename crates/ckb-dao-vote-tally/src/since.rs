@@ -0,0 +1,81 @@
+// Off-chain counterpart of the since comparison in the type script, applied to
+// the block that committed a ballot.
+
+const SINCE_RELATIVE_FLAG: u64 = 1 << 63;
+const SINCE_METRIC_MASK: u64 = 0b0110_0000 << 56;
+const SINCE_METRIC_BLOCK_NUMBER: u64 = 0;
+const SINCE_METRIC_EPOCH: u64 = 0b0010_0000 << 56;
+const SINCE_METRIC_TIMESTAMP: u64 = 0b0100_0000 << 56;
+const SINCE_VALUE_MASK: u64 = (1 << 56) - 1;
+
+/// Position of the block that committed a transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockInfo {
+    pub number: u64,
+    /// block timestamp in milliseconds
+    pub timestamp: u64,
+    /// full value of the block's EpochNumberWithFraction
+    pub epoch: u64,
+    /// position of the transaction in the block
+    pub tx_index: usize,
+}
+
+fn epoch_parts(epoch: u64) -> (u64, u64, u64) {
+    let number = epoch & 0xff_ffff;
+    let index = (epoch >> 24) & 0xffff;
+    let length = (epoch >> 40) & 0xffff;
+    (number, index, length)
+}
+
+fn epoch_reached(current: u64, end: u64) -> bool {
+    let (number, index, length) = epoch_parts(current);
+    let (end_number, end_index, end_length) = epoch_parts(end);
+    number > end_number || (number == end_number && index * end_length >= end_index * length)
+}
+
+/// Whether `block` is at or after the absolute `since`. Relative or malformed
+/// values are never reached, as in the type script.
+pub fn since_reached(block: &BlockInfo, since: u64) -> bool {
+    if since & SINCE_RELATIVE_FLAG != 0 {
+        return false;
+    }
+    let value = since & SINCE_VALUE_MASK;
+    match since & SINCE_METRIC_MASK {
+        SINCE_METRIC_BLOCK_NUMBER => block.number >= value,
+        SINCE_METRIC_EPOCH => epoch_reached(block.epoch, value),
+        // header timestamp is in milliseconds, since timestamp in seconds
+        SINCE_METRIC_TIMESTAMP => block.timestamp / 1000 >= value,
+        _ => false,
+    }
+}
+
+/// Whether `since` is absolute, with a known metric and a well formed epoch,
+/// as the type script requires of the `end_time` of a guarded session.
+pub fn is_absolute_since(since: u64) -> bool {
+    const SINCE_RESERVED_MASK: u64 = 0b0001_1111 << 56;
+    if since & SINCE_RELATIVE_FLAG != 0 || since & SINCE_RESERVED_MASK != 0 {
+        return false;
+    }
+    match since & SINCE_METRIC_MASK {
+        SINCE_METRIC_BLOCK_NUMBER | SINCE_METRIC_TIMESTAMP => true,
+        SINCE_METRIC_EPOCH => {
+            let (_, index, length) = epoch_parts(since & SINCE_VALUE_MASK);
+            index < length
+        }
+        _ => false,
+    }
+}
+
+/// Relative epoch duration of a since as `(number, index, length)`, used by
+/// escrow locks. A zero length with a zero index counts whole epochs and is
+/// returned with a length of 1.
+pub fn relative_epochs(since: u64) -> Option<(u64, u64, u64)> {
+    if since & SINCE_RELATIVE_FLAG == 0 || since & SINCE_METRIC_MASK != SINCE_METRIC_EPOCH {
+        return None;
+    }
+    match epoch_parts(since & SINCE_VALUE_MASK) {
+        (number, 0, 0) => Some((number, 0, 1)),
+        (number, index, length) if index < length => Some((number, index, length)),
+        _ => None,
+    }
+}
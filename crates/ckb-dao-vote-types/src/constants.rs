@@ -0,0 +1,57 @@
+//! Constants of the ckb-dao-vote specification.
+
+/// Value of every leaf in the eligibility SMT, keyed by the voter's lock script hash.
+pub const SMT_VALUE: [u8; 32] = [
+    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+];
+
+// type script args lengths
+/// blake160 of vote meta cell out point
+pub const VOTE_ARGS_LEN: usize = 20;
+/// blake160 of vote meta cell out point + session end_time (absolute since)
+pub const GUARDED_ARGS_LEN: usize = 28;
+/// the type script of vote meta cells has a 1-byte tag as args. Empty args
+/// remain plain vote cells, as before vote meta type scripts existed.
+pub const META_ARGS: [u8; 1] = [0x01];
+/// length of `META_ARGS`
+pub const META_ARGS_LEN: usize = META_ARGS.len();
+/// type id of the tally cell
+pub const TALLY_ARGS_LEN: usize = 32;
+/// type id of the tally cell + 0
+pub const RESULT_ARGS_LEN: usize = 33;
+
+/// VoteMeta without a version field is version 1
+pub const VOTE_META_V1: u8 = 1;
+/// version 2: ballots record their weight
+pub const VOTE_META_V2: u8 = 2;
+/// length of a version 1 ballot, or of the bitmap in a version 2 ballot
+pub const BITMAP_LEN: usize = 4;
+
+/// basis points: 10000 is 100%
+pub const BPS: u128 = 10000;
+
+/// every ballot counts as 1
+pub const WEIGHT_MODE_BALLOT: u8 = 0;
+/// every ballot counts as the capacity of its vote cell, in shannons
+pub const WEIGHT_MODE_CAPACITY: u8 = 1;
+/// the capacity of a time-locked vote cell, multiplied by the lock duration
+pub const WEIGHT_MODE_ESCROW: u8 = 2;
+/// escrow lock args: blake160 of the owner + relative since
+pub const ESCROW_LOCK_ARGS_LEN: usize = 28;
+
+// closure policy flags
+/// the vote meta cell can only be consumed at or after `end_time`
+pub const CLOSE_AFTER_END: u8 = 1;
+/// consuming the vote meta cell needs `committee_threshold` committee members
+pub const CLOSE_BY_COMMITTEE: u8 = 2;
+
+/// voters are proven by `smt_root_hash` only
+pub const ELIGIBILITY_SMT: u8 = 0;
+/// voters also prove a deposit made at or before the snapshot block
+pub const ELIGIBILITY_DEPOSIT_SNAPSHOT: u8 = 1;
+
+/// type script hash of Nervos DAO cells on mainnet and testnet
+pub const DAO_TYPE_HASH: [u8; 32] = [
+    0xcc, 0x77, 0xc4, 0xde, 0xac, 0x05, 0xd6, 0x8a, 0xb5, 0xb2, 0x68, 0x28, 0xf0, 0xbf, 0x45, 0x65,
+    0xa8, 0xd7, 0x31, 0x13, 0xd7, 0xbb, 0x7e, 0x92, 0xb8, 0x36, 0x2b, 0x8a, 0x74, 0xe5, 0x8e, 0x58,
+];
@@ -0,0 +1,135 @@
+//! Exit codes of the ckb-dao-vote type script.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(i8)]
+pub enum ErrorCode {
+    IndexOutOfBound = 21,
+    ItemMissing = 22,
+    LengthNotEnough = 23,
+    Encoding = 24,
+    WaitFailure = 25,
+    UnknownSyscall = 26,
+    Molecule = 51,
+    WrongTxType = 52,
+    WrongArgs = 53,
+    NoMetaCell = 54,
+    VerifySmtFail = 55,
+    NoLockFound = 56,
+    WrongVoteCandidate = 57,
+    WrongVoteCapacity = 58,
+    EarlyConsumption = 59,
+    WrongTally = 60,
+    WrongWeightMode = 61,
+    WrongResult = 62,
+    WrongCandidateRecord = 63,
+    UnsupportedVersion = 64,
+    WrongBond = 65,
+    WrongClosure = 66,
+    WrongEligibility = 67,
+    WrongBallotWeight = 68,
+    MissingWitness = 69,
+    CorruptWitness = 70,
+    MissingMeta = 71,
+    CorruptMeta = 72,
+    MissingTally = 73,
+    CorruptTally = 74,
+    MissingResult = 75,
+    CorruptResult = 76,
+    MissingTx = 77,
+    CorruptTx = 78,
+    WrongBallotLength = 79,
+    AbstainNotAllowed = 80,
+    TooManyCandidates = 81,
+}
+
+impl ErrorCode {
+    pub const ALL: &'static [ErrorCode] = &[
+        ErrorCode::IndexOutOfBound,
+        ErrorCode::ItemMissing,
+        ErrorCode::LengthNotEnough,
+        ErrorCode::Encoding,
+        ErrorCode::WaitFailure,
+        ErrorCode::UnknownSyscall,
+        ErrorCode::Molecule,
+        ErrorCode::WrongTxType,
+        ErrorCode::WrongArgs,
+        ErrorCode::NoMetaCell,
+        ErrorCode::VerifySmtFail,
+        ErrorCode::NoLockFound,
+        ErrorCode::WrongVoteCandidate,
+        ErrorCode::WrongVoteCapacity,
+        ErrorCode::EarlyConsumption,
+        ErrorCode::WrongTally,
+        ErrorCode::WrongWeightMode,
+        ErrorCode::WrongResult,
+        ErrorCode::WrongCandidateRecord,
+        ErrorCode::UnsupportedVersion,
+        ErrorCode::WrongBond,
+        ErrorCode::WrongClosure,
+        ErrorCode::WrongEligibility,
+        ErrorCode::WrongBallotWeight,
+        ErrorCode::MissingWitness,
+        ErrorCode::CorruptWitness,
+        ErrorCode::MissingMeta,
+        ErrorCode::CorruptMeta,
+        ErrorCode::MissingTally,
+        ErrorCode::CorruptTally,
+        ErrorCode::MissingResult,
+        ErrorCode::CorruptResult,
+        ErrorCode::MissingTx,
+        ErrorCode::CorruptTx,
+        ErrorCode::WrongBallotLength,
+        ErrorCode::AbstainNotAllowed,
+        ErrorCode::TooManyCandidates,
+    ];
+
+    pub fn code(self) -> i8 {
+        self as i8
+    }
+
+    pub fn from_code(code: i8) -> Option<Self> {
+        Self::ALL.iter().copied().find(|e| e.code() == code)
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            ErrorCode::IndexOutOfBound => "syscall: index out of bound",
+            ErrorCode::ItemMissing => "syscall: item missing",
+            ErrorCode::LengthNotEnough => "syscall: length not enough",
+            ErrorCode::Encoding => "syscall: encoding error",
+            ErrorCode::WaitFailure => "syscall: wait failure",
+            ErrorCode::UnknownSyscall => "syscall: unknown error",
+            ErrorCode::Molecule => "malformed molecule data",
+            ErrorCode::WrongTxType => "transaction is neither a vote creation nor a consumption",
+            ErrorCode::WrongArgs => "wrong type script args",
+            ErrorCode::NoMetaCell => "vote meta cell not found in cell deps",
+            ErrorCode::VerifySmtFail => "voter is not in the eligibility SMT",
+            ErrorCode::NoLockFound => "no input cell with the voter's lock script",
+            ErrorCode::WrongVoteCandidate => "ballot chooses a candidate that doesn't exist",
+            ErrorCode::WrongVoteCapacity => "vote cell capacity out of range",
+            ErrorCode::EarlyConsumption => "vote cell consumed before the session ends",
+            ErrorCode::WrongTally => "invalid tally cell",
+            ErrorCode::WrongWeightMode => "invalid weight mode",
+            ErrorCode::WrongResult => "invalid result cell",
+            ErrorCode::WrongCandidateRecord => "invalid candidate records",
+            ErrorCode::UnsupportedVersion => "unsupported vote meta version",
+            ErrorCode::WrongBond => "proposer bond not satisfied",
+            ErrorCode::WrongClosure => "closure policy not satisfied",
+            ErrorCode::WrongEligibility => "deposit snapshot eligibility not proven",
+            ErrorCode::WrongBallotWeight => "recorded ballot weight mismatch",
+            ErrorCode::MissingWitness => "witness cannot be loaded",
+            ErrorCode::CorruptWitness => "witness is not a WitnessArgs with a valid vote proof",
+            ErrorCode::MissingMeta => "vote meta cell data cannot be loaded",
+            ErrorCode::CorruptMeta => "vote meta cell data is malformed",
+            ErrorCode::MissingTally => "tally cell data cannot be loaded",
+            ErrorCode::CorruptTally => "tally cell data is malformed",
+            ErrorCode::MissingResult => "result cell data cannot be loaded",
+            ErrorCode::CorruptResult => "result cell data is malformed",
+            ErrorCode::MissingTx => "transaction cannot be loaded",
+            ErrorCode::CorruptTx => "transaction is malformed",
+            ErrorCode::WrongBallotLength => "wrong ballot data length for the version",
+            ErrorCode::AbstainNotAllowed => "empty ballot in a session that doesn't allow abstain",
+            ErrorCode::TooManyCandidates => "session has more than 32 candidates",
+        }
+    }
+}
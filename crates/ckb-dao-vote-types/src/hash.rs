@@ -0,0 +1,17 @@
+use blake2b_ref::Blake2bBuilder;
+
+const PERSONALIZATION: &[u8] = b"ckb-default-hash";
+
+/// `ckbhash`: blake2b-256 with the CKB personalization.
+pub fn blake2b_256<T: AsRef<[u8]>>(bytes: T) -> [u8; 32] {
+    let mut blake2b = Blake2bBuilder::new(32).personal(PERSONALIZATION).build();
+    blake2b.update(bytes.as_ref());
+    let mut ret = [0; 32];
+    blake2b.finalize(&mut ret);
+    ret
+}
+
+/// The leading 20 bytes of `ckbhash`.
+pub fn blake160(bytes: &[u8]) -> [u8; 20] {
+    blake2b_256(bytes)[0..20].try_into().unwrap()
+}
@@ -0,0 +1,16 @@
+//! Types shared by the ckb-dao-vote scripts and off-chain code: the Molecule
+//! schema, SMT hasher, constants and error codes.
+//!
+//! The crate is `no_std` by default. The `std` feature adds serde models of the
+//! schema for building cell data and witnesses.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod constants;
+pub mod error;
+pub mod hash;
+#[cfg(feature = "std")]
+pub mod models;
+pub mod molecules;
+pub mod smt_hasher;
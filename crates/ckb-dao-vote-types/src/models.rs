@@ -0,0 +1,84 @@
+//! Serde models of the schema in `molecules/vote.mol`, for building cell data
+//! and witnesses off-chain with `serde_molecule::to_vec`.
+
+use serde::{Deserialize, Serialize};
+use serde_molecule::dynvec_serde;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WitnessArgs {
+    pub lock: Option<Vec<u8>>,
+    pub input_type: Option<Vec<u8>>,
+    pub output_type: Option<Vec<u8>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct VoteMeta {
+    pub smt_root_hash: Option<[u8; 32]>,
+    #[serde(with = "dynvec_serde")]
+    pub candidates: Vec<Vec<u8>>,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub extra: Option<Vec<u8>>,
+    pub min_vote_capacity: Option<u64>,
+    pub max_vote_capacity: Option<u64>,
+    pub guarded_consumption: Option<u8>,
+    pub tally_type_hash: Option<[u8; 32]>,
+    pub weight_mode: Option<u8>,
+    pub eligible_weight: Option<u64>,
+    pub quorum: Option<u64>,
+    pub quorum_bps: Option<u32>,
+    pub pass_threshold_bps: Option<u32>,
+    pub allow_abstain: Option<u8>,
+    pub candidate_records: Option<CandidateVec>,
+    pub version: Option<u8>,
+    pub proposer_bond: Option<u64>,
+    pub bond_forfeit_lock_hash: Option<[u8; 32]>,
+    pub closure_policy: Option<u8>,
+    pub committee: Option<Vec<[u8; 32]>>,
+    pub committee_threshold: Option<u8>,
+    pub eligibility_mode: Option<u8>,
+    pub snapshot_block_hash: Option<[u8; 32]>,
+    pub deposit_type_hash: Option<[u8; 32]>,
+    pub escrow_lock_code_hash: Option<[u8; 32]>,
+    pub escrow_min_epochs: Option<u64>,
+    pub escrow_max_epochs: Option<u64>,
+    pub escrow_lock_hash_type: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Candidate {
+    pub id: u32,
+    pub title: Vec<u8>,
+    pub content_hash: [u8; 32],
+    pub recipient_lock_hash: Option<[u8; 32]>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CandidateVec(#[serde(with = "dynvec_serde")] pub Vec<Candidate>);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VoteProof {
+    pub lock_script_hash: [u8; 32],
+    pub smt_proof: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Tally {
+    pub session_id: [u8; 20],
+    pub counts: Vec<u64>,
+    pub turnout: u64,
+    pub abstain: u64,
+    pub voted_root: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VoteResult {
+    pub session_id: [u8; 20],
+    pub meta_hash: [u8; 32],
+    pub winners: u32,
+    pub counts: Vec<u64>,
+    pub turnout: u64,
+    pub abstain: u64,
+    pub quorum_reached: u8,
+    pub passed: u8,
+}
@@ -0,0 +1,115 @@
+#[allow(clippy::all, unused_imports, dead_code)]
+pub mod ckb;
+#[allow(clippy::all, unused_imports, dead_code)]
+pub mod vote;
+
+use molecule::lazy_reader::Error;
+use vote::{Byte32Vec, CandidateVec, VoteMeta};
+
+// Fields of `VoteMeta` up to and including `extra` are always present. Fields
+// appended after it are optional: meta cells created before a field existed
+// simply stop earlier, and a missing trailing field reads as `None`.
+pub const VOTE_META_BASE_FIELD_COUNT: usize = 5;
+pub const VOTE_META_FIELD_COUNT: usize = 29;
+
+macro_rules! trailing_field {
+    ($name:ident, $getter:ident, $index:expr, $ty:ty) => {
+        pub fn $name(&self) -> Result<Option<$ty>, Error> {
+            if self.has_field($index)? {
+                Ok(self.$getter()?)
+            } else {
+                Ok(None)
+            }
+        }
+    };
+}
+
+impl VoteMeta {
+    pub fn has_field(&self, field_index: usize) -> Result<bool, Error> {
+        Ok(self.cursor.table_actual_field_count()? > field_index)
+    }
+
+    trailing_field!(min_vote_capacity_opt, min_vote_capacity, 5, u64);
+    trailing_field!(max_vote_capacity_opt, max_vote_capacity, 6, u64);
+    trailing_field!(guarded_consumption_opt, guarded_consumption, 7, u8);
+    trailing_field!(tally_type_hash_opt, tally_type_hash, 8, [u8; 32]);
+    trailing_field!(weight_mode_opt, weight_mode, 9, u8);
+    trailing_field!(eligible_weight_opt, eligible_weight, 10, u64);
+    trailing_field!(quorum_opt, quorum, 11, u64);
+    trailing_field!(quorum_bps_opt, quorum_bps, 12, u32);
+    trailing_field!(pass_threshold_bps_opt, pass_threshold_bps, 13, u32);
+    trailing_field!(allow_abstain_opt, allow_abstain, 14, u8);
+    trailing_field!(candidate_records_opt, candidate_records, 15, CandidateVec);
+    trailing_field!(version_opt, version, 16, u8);
+    trailing_field!(proposer_bond_opt, proposer_bond, 17, u64);
+    trailing_field!(
+        bond_forfeit_lock_hash_opt,
+        bond_forfeit_lock_hash,
+        18,
+        [u8; 32]
+    );
+    trailing_field!(closure_policy_opt, closure_policy, 19, u8);
+    trailing_field!(committee_opt, committee, 20, Byte32Vec);
+    trailing_field!(committee_threshold_opt, committee_threshold, 21, u8);
+    trailing_field!(eligibility_mode_opt, eligibility_mode, 22, u8);
+    trailing_field!(snapshot_block_hash_opt, snapshot_block_hash, 23, [u8; 32]);
+    trailing_field!(deposit_type_hash_opt, deposit_type_hash, 24, [u8; 32]);
+    trailing_field!(
+        escrow_lock_code_hash_opt,
+        escrow_lock_code_hash,
+        25,
+        [u8; 32]
+    );
+    trailing_field!(escrow_min_epochs_opt, escrow_min_epochs, 26, u64);
+    trailing_field!(escrow_max_epochs_opt, escrow_max_epochs, 27, u64);
+    trailing_field!(escrow_lock_hash_type_opt, escrow_lock_hash_type, 28, u8);
+
+    /// Verifies a vote meta cell of any layout: the generated `verify` when
+    /// all fields are present, `verify_partial` otherwise.
+    pub fn verify_compatible(&self) -> Result<(), Error> {
+        if self.cursor.table_actual_field_count()? < VOTE_META_FIELD_COUNT {
+            self.verify_partial()
+        } else {
+            self.verify(true)
+        }
+    }
+
+    // Same checks as the generated `verify`, but tolerates missing trailing fields.
+    pub fn verify_partial(&self) -> Result<(), Error> {
+        self.cursor.verify_table(VOTE_META_BASE_FIELD_COUNT, true)?;
+        self.smt_root_hash()?;
+        self.candidates()?.verify(true)?;
+        self.cursor.table_slice_by_index(2)?.verify_fixed_size(8)?;
+        self.cursor.table_slice_by_index(3)?.verify_fixed_size(8)?;
+        self.extra()?;
+        self.min_vote_capacity_opt()?;
+        self.max_vote_capacity_opt()?;
+        self.guarded_consumption_opt()?;
+        self.tally_type_hash_opt()?;
+        self.weight_mode_opt()?;
+        self.eligible_weight_opt()?;
+        self.quorum_opt()?;
+        self.quorum_bps_opt()?;
+        self.pass_threshold_bps_opt()?;
+        self.allow_abstain_opt()?;
+        if let Some(records) = self.candidate_records_opt()? {
+            records.verify(true)?;
+        }
+        self.version_opt()?;
+        self.proposer_bond_opt()?;
+        self.bond_forfeit_lock_hash_opt()?;
+        self.closure_policy_opt()?;
+        if let Some(committee) = self.committee_opt()? {
+            committee.verify(true)?;
+        }
+        self.committee_threshold_opt()?;
+        self.eligibility_mode_opt()?;
+        self.snapshot_block_hash_opt()?;
+        self.deposit_type_hash_opt()?;
+        self.escrow_lock_code_hash_opt()?;
+        self.escrow_min_epochs_opt()?;
+        self.escrow_max_epochs_opt()?;
+        self.escrow_lock_hash_type_opt()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,1233 @@
+extern crate alloc;
+use core::convert::TryInto;
+use molecule::lazy_reader::{Cursor, Error, NUMBER_SIZE};
+#[derive(Clone)]
+pub struct Uint32 {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for Uint32 {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+impl Uint32 {
+    pub fn len(&self) -> usize {
+        4
+    }
+}
+impl Uint32 {
+    pub fn get(&self, index: usize) -> Result<u8, Error> {
+        let cur = self.cursor.slice_by_offset(1usize * index, 1usize)?;
+        cur.try_into()
+    }
+}
+impl Uint32 {
+    pub fn verify(&self, _compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_fixed_size(4usize)?;
+        Ok(())
+    }
+}
+pub struct Uint32Opt {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for Uint32Opt {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+#[derive(Clone)]
+pub struct Uint64 {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for Uint64 {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+impl Uint64 {
+    pub fn len(&self) -> usize {
+        8
+    }
+}
+impl Uint64 {
+    pub fn get(&self, index: usize) -> Result<u8, Error> {
+        let cur = self.cursor.slice_by_offset(1usize * index, 1usize)?;
+        cur.try_into()
+    }
+}
+impl Uint64 {
+    pub fn verify(&self, _compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_fixed_size(8usize)?;
+        Ok(())
+    }
+}
+pub struct Uint64Opt {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for Uint64Opt {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+#[derive(Clone)]
+pub struct Uint64Vec {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for Uint64Vec {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+impl Uint64Vec {
+    pub fn len(&self) -> Result<usize, Error> {
+        self.cursor.fixvec_length()
+    }
+}
+impl Uint64Vec {
+    pub fn get(&self, index: usize) -> Result<u64, Error> {
+        let cur = self.cursor.fixvec_slice_by_index(8usize, index)?;
+        cur.try_into()
+    }
+}
+pub struct Uint64VecIterator {
+    cur: Uint64Vec,
+    index: usize,
+    len: usize,
+}
+impl core::iter::Iterator for Uint64VecIterator {
+    type Item = u64;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            None
+        } else {
+            let res = self.cur.get(self.index).unwrap();
+            self.index += 1;
+            Some(res)
+        }
+    }
+}
+impl core::iter::IntoIterator for Uint64Vec {
+    type Item = u64;
+    type IntoIter = Uint64VecIterator;
+    fn into_iter(self) -> Self::IntoIter {
+        let len = self.len().unwrap();
+        Self::IntoIter {
+            cur: self,
+            index: 0,
+            len,
+        }
+    }
+}
+pub struct Uint64VecIteratorRef<'a> {
+    cur: &'a Uint64Vec,
+    index: usize,
+    len: usize,
+}
+impl<'a> core::iter::Iterator for Uint64VecIteratorRef<'a> {
+    type Item = u64;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            None
+        } else {
+            let res = self.cur.get(self.index).unwrap();
+            self.index += 1;
+            Some(res)
+        }
+    }
+}
+impl Uint64Vec {
+    pub fn iter(&self) -> Uint64VecIteratorRef {
+        let len = self.len().unwrap();
+        Uint64VecIteratorRef {
+            cur: &self,
+            index: 0,
+            len,
+        }
+    }
+}
+impl Uint64Vec {
+    pub fn verify(&self, _compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_fixvec(8usize)?;
+        Ok(())
+    }
+}
+pub struct ByteOpt {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for ByteOpt {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+#[derive(Clone)]
+pub struct Bytes {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for Bytes {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+impl Bytes {
+    pub fn len(&self) -> Result<usize, Error> {
+        self.cursor.fixvec_length()
+    }
+}
+impl Bytes {
+    pub fn get(&self, index: usize) -> Result<u8, Error> {
+        let cur = self.cursor.fixvec_slice_by_index(1usize, index)?;
+        cur.try_into()
+    }
+}
+pub struct BytesIterator {
+    cur: Bytes,
+    index: usize,
+    len: usize,
+}
+impl core::iter::Iterator for BytesIterator {
+    type Item = u8;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            None
+        } else {
+            let res = self.cur.get(self.index).unwrap();
+            self.index += 1;
+            Some(res)
+        }
+    }
+}
+impl core::iter::IntoIterator for Bytes {
+    type Item = u8;
+    type IntoIter = BytesIterator;
+    fn into_iter(self) -> Self::IntoIter {
+        let len = self.len().unwrap();
+        Self::IntoIter {
+            cur: self,
+            index: 0,
+            len,
+        }
+    }
+}
+pub struct BytesIteratorRef<'a> {
+    cur: &'a Bytes,
+    index: usize,
+    len: usize,
+}
+impl<'a> core::iter::Iterator for BytesIteratorRef<'a> {
+    type Item = u8;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            None
+        } else {
+            let res = self.cur.get(self.index).unwrap();
+            self.index += 1;
+            Some(res)
+        }
+    }
+}
+impl Bytes {
+    pub fn iter(&self) -> BytesIteratorRef {
+        let len = self.len().unwrap();
+        BytesIteratorRef {
+            cur: &self,
+            index: 0,
+            len,
+        }
+    }
+}
+impl Bytes {
+    pub fn verify(&self, _compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_fixvec(1usize)?;
+        Ok(())
+    }
+}
+pub struct BytesOpt {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for BytesOpt {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+#[derive(Clone)]
+pub struct String {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for String {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+impl String {
+    pub fn len(&self) -> Result<usize, Error> {
+        self.cursor.fixvec_length()
+    }
+}
+impl String {
+    pub fn get(&self, index: usize) -> Result<u8, Error> {
+        let cur = self.cursor.fixvec_slice_by_index(1usize, index)?;
+        cur.try_into()
+    }
+}
+pub struct StringIterator {
+    cur: String,
+    index: usize,
+    len: usize,
+}
+impl core::iter::Iterator for StringIterator {
+    type Item = u8;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            None
+        } else {
+            let res = self.cur.get(self.index).unwrap();
+            self.index += 1;
+            Some(res)
+        }
+    }
+}
+impl core::iter::IntoIterator for String {
+    type Item = u8;
+    type IntoIter = StringIterator;
+    fn into_iter(self) -> Self::IntoIter {
+        let len = self.len().unwrap();
+        Self::IntoIter {
+            cur: self,
+            index: 0,
+            len,
+        }
+    }
+}
+pub struct StringIteratorRef<'a> {
+    cur: &'a String,
+    index: usize,
+    len: usize,
+}
+impl<'a> core::iter::Iterator for StringIteratorRef<'a> {
+    type Item = u8;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            None
+        } else {
+            let res = self.cur.get(self.index).unwrap();
+            self.index += 1;
+            Some(res)
+        }
+    }
+}
+impl String {
+    pub fn iter(&self) -> StringIteratorRef {
+        let len = self.len().unwrap();
+        StringIteratorRef {
+            cur: &self,
+            index: 0,
+            len,
+        }
+    }
+}
+impl String {
+    pub fn verify(&self, _compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_fixvec(1usize)?;
+        Ok(())
+    }
+}
+pub struct StringOpt {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for StringOpt {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+#[derive(Clone)]
+pub struct StringVec {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for StringVec {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+impl StringVec {
+    pub fn len(&self) -> Result<usize, Error> {
+        self.cursor.dynvec_length()
+    }
+}
+impl StringVec {
+    pub fn get(&self, index: usize) -> Result<Cursor, Error> {
+        let cur = self.cursor.dynvec_slice_by_index(index)?;
+        cur.convert_to_rawbytes()
+    }
+}
+pub struct StringVecIterator {
+    cur: StringVec,
+    index: usize,
+    len: usize,
+}
+impl core::iter::Iterator for StringVecIterator {
+    type Item = Cursor;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            None
+        } else {
+            let res = self.cur.get(self.index).unwrap();
+            self.index += 1;
+            Some(res)
+        }
+    }
+}
+impl core::iter::IntoIterator for StringVec {
+    type Item = Cursor;
+    type IntoIter = StringVecIterator;
+    fn into_iter(self) -> Self::IntoIter {
+        let len = self.len().unwrap();
+        Self::IntoIter {
+            cur: self,
+            index: 0,
+            len,
+        }
+    }
+}
+pub struct StringVecIteratorRef<'a> {
+    cur: &'a StringVec,
+    index: usize,
+    len: usize,
+}
+impl<'a> core::iter::Iterator for StringVecIteratorRef<'a> {
+    type Item = Cursor;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            None
+        } else {
+            let res = self.cur.get(self.index).unwrap();
+            self.index += 1;
+            Some(res)
+        }
+    }
+}
+impl StringVec {
+    pub fn iter(&self) -> StringVecIteratorRef {
+        let len = self.len().unwrap();
+        StringVecIteratorRef {
+            cur: &self,
+            index: 0,
+            len,
+        }
+    }
+}
+impl StringVec {
+    pub fn verify(&self, _compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_dynvec()?;
+        Ok(())
+    }
+}
+#[derive(Clone)]
+pub struct Byte20 {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for Byte20 {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+impl Byte20 {
+    pub fn len(&self) -> usize {
+        20
+    }
+}
+impl Byte20 {
+    pub fn get(&self, index: usize) -> Result<u8, Error> {
+        let cur = self.cursor.slice_by_offset(1usize * index, 1usize)?;
+        cur.try_into()
+    }
+}
+impl Byte20 {
+    pub fn verify(&self, _compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_fixed_size(20usize)?;
+        Ok(())
+    }
+}
+#[derive(Clone)]
+pub struct Byte32 {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for Byte32 {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+impl Byte32 {
+    pub fn len(&self) -> usize {
+        32
+    }
+}
+impl Byte32 {
+    pub fn get(&self, index: usize) -> Result<u8, Error> {
+        let cur = self.cursor.slice_by_offset(1usize * index, 1usize)?;
+        cur.try_into()
+    }
+}
+impl Byte32 {
+    pub fn verify(&self, _compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_fixed_size(32usize)?;
+        Ok(())
+    }
+}
+pub struct Byte32Opt {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for Byte32Opt {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+#[derive(Clone)]
+pub struct Byte32Vec {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for Byte32Vec {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+impl Byte32Vec {
+    pub fn len(&self) -> Result<usize, Error> {
+        self.cursor.fixvec_length()
+    }
+}
+impl Byte32Vec {
+    pub fn get(&self, index: usize) -> Result<[u8; 32usize], Error> {
+        let cur = self.cursor.fixvec_slice_by_index(32usize, index)?;
+        cur.try_into()
+    }
+}
+pub struct Byte32VecIterator {
+    cur: Byte32Vec,
+    index: usize,
+    len: usize,
+}
+impl core::iter::Iterator for Byte32VecIterator {
+    type Item = [u8; 32usize];
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            None
+        } else {
+            let res = self.cur.get(self.index).unwrap();
+            self.index += 1;
+            Some(res)
+        }
+    }
+}
+impl core::iter::IntoIterator for Byte32Vec {
+    type Item = [u8; 32usize];
+    type IntoIter = Byte32VecIterator;
+    fn into_iter(self) -> Self::IntoIter {
+        let len = self.len().unwrap();
+        Self::IntoIter {
+            cur: self,
+            index: 0,
+            len,
+        }
+    }
+}
+pub struct Byte32VecIteratorRef<'a> {
+    cur: &'a Byte32Vec,
+    index: usize,
+    len: usize,
+}
+impl<'a> core::iter::Iterator for Byte32VecIteratorRef<'a> {
+    type Item = [u8; 32usize];
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            None
+        } else {
+            let res = self.cur.get(self.index).unwrap();
+            self.index += 1;
+            Some(res)
+        }
+    }
+}
+impl Byte32Vec {
+    pub fn iter(&self) -> Byte32VecIteratorRef {
+        let len = self.len().unwrap();
+        Byte32VecIteratorRef {
+            cur: &self,
+            index: 0,
+            len,
+        }
+    }
+}
+impl Byte32Vec {
+    pub fn verify(&self, _compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_fixvec(32usize)?;
+        Ok(())
+    }
+}
+pub struct Byte32VecOpt {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for Byte32VecOpt {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+#[derive(Clone)]
+pub struct Candidate {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for Candidate {
+    fn from(cursor: Cursor) -> Self {
+        Candidate { cursor }
+    }
+}
+impl Candidate {
+    pub fn id(&self) -> Result<u32, Error> {
+        let cur = self.cursor.table_slice_by_index(0usize)?;
+        cur.try_into()
+    }
+}
+impl Candidate {
+    pub fn title(&self) -> Result<Cursor, Error> {
+        let cur = self.cursor.table_slice_by_index(1usize)?;
+        cur.convert_to_rawbytes()
+    }
+}
+impl Candidate {
+    pub fn content_hash(&self) -> Result<[u8; 32usize], Error> {
+        let cur = self.cursor.table_slice_by_index(2usize)?;
+        cur.try_into()
+    }
+}
+impl Candidate {
+    pub fn recipient_lock_hash(&self) -> Result<Option<[u8; 32usize]>, Error> {
+        let cur = self.cursor.table_slice_by_index(3usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl Candidate {
+    pub fn verify(&self, compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_table(4usize, compatible)?;
+        Byte32::from(Cursor::try_from(self.content_hash()?)?).verify(compatible)?;
+        let val = self.recipient_lock_hash()?;
+        if val.is_some() {
+            let val = val.unwrap();
+            Byte32::from(Cursor::try_from(val)?).verify(compatible)?;
+        }
+        Ok(())
+    }
+}
+#[derive(Clone)]
+pub struct CandidateVec {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for CandidateVec {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+impl CandidateVec {
+    pub fn len(&self) -> Result<usize, Error> {
+        self.cursor.dynvec_length()
+    }
+}
+impl CandidateVec {
+    pub fn get(&self, index: usize) -> Result<Candidate, Error> {
+        let cur = self.cursor.dynvec_slice_by_index(index)?;
+        Ok(cur.into())
+    }
+}
+pub struct CandidateVecIterator {
+    cur: CandidateVec,
+    index: usize,
+    len: usize,
+}
+impl core::iter::Iterator for CandidateVecIterator {
+    type Item = Candidate;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            None
+        } else {
+            let res = self.cur.get(self.index).unwrap();
+            self.index += 1;
+            Some(res)
+        }
+    }
+}
+impl core::iter::IntoIterator for CandidateVec {
+    type Item = Candidate;
+    type IntoIter = CandidateVecIterator;
+    fn into_iter(self) -> Self::IntoIter {
+        let len = self.len().unwrap();
+        Self::IntoIter {
+            cur: self,
+            index: 0,
+            len,
+        }
+    }
+}
+pub struct CandidateVecIteratorRef<'a> {
+    cur: &'a CandidateVec,
+    index: usize,
+    len: usize,
+}
+impl<'a> core::iter::Iterator for CandidateVecIteratorRef<'a> {
+    type Item = Candidate;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            None
+        } else {
+            let res = self.cur.get(self.index).unwrap();
+            self.index += 1;
+            Some(res)
+        }
+    }
+}
+impl CandidateVec {
+    pub fn iter(&self) -> CandidateVecIteratorRef {
+        let len = self.len().unwrap();
+        CandidateVecIteratorRef {
+            cur: &self,
+            index: 0,
+            len,
+        }
+    }
+}
+impl CandidateVec {
+    pub fn verify(&self, compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_dynvec()?;
+        for i in 0..self.len()? {
+            self.get(i)?.verify(compatible)?;
+        }
+        Ok(())
+    }
+}
+pub struct CandidateVecOpt {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for CandidateVecOpt {
+    fn from(cursor: Cursor) -> Self {
+        Self { cursor }
+    }
+}
+#[derive(Clone)]
+pub struct VoteMeta {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for VoteMeta {
+    fn from(cursor: Cursor) -> Self {
+        VoteMeta { cursor }
+    }
+}
+impl VoteMeta {
+    pub fn smt_root_hash(&self) -> Result<Option<[u8; 32usize]>, Error> {
+        let cur = self.cursor.table_slice_by_index(0usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn candidates(&self) -> Result<StringVec, Error> {
+        let cur = self.cursor.table_slice_by_index(1usize)?;
+        Ok(cur.into())
+    }
+}
+impl VoteMeta {
+    pub fn start_time(&self) -> Result<u64, Error> {
+        let cur = self.cursor.table_slice_by_index(2usize)?;
+        cur.try_into()
+    }
+}
+impl VoteMeta {
+    pub fn end_time(&self) -> Result<u64, Error> {
+        let cur = self.cursor.table_slice_by_index(3usize)?;
+        cur.try_into()
+    }
+}
+impl VoteMeta {
+    pub fn extra(&self) -> Result<Option<Cursor>, Error> {
+        let cur = self.cursor.table_slice_by_index(4usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            let cur = cur.convert_to_rawbytes()?;
+            Ok(Some(cur.into()))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn min_vote_capacity(&self) -> Result<Option<u64>, Error> {
+        let cur = self.cursor.table_slice_by_index(5usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn max_vote_capacity(&self) -> Result<Option<u64>, Error> {
+        let cur = self.cursor.table_slice_by_index(6usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn guarded_consumption(&self) -> Result<Option<u8>, Error> {
+        let cur = self.cursor.table_slice_by_index(7usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn tally_type_hash(&self) -> Result<Option<[u8; 32usize]>, Error> {
+        let cur = self.cursor.table_slice_by_index(8usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn weight_mode(&self) -> Result<Option<u8>, Error> {
+        let cur = self.cursor.table_slice_by_index(9usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn eligible_weight(&self) -> Result<Option<u64>, Error> {
+        let cur = self.cursor.table_slice_by_index(10usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn quorum(&self) -> Result<Option<u64>, Error> {
+        let cur = self.cursor.table_slice_by_index(11usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn quorum_bps(&self) -> Result<Option<u32>, Error> {
+        let cur = self.cursor.table_slice_by_index(12usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn pass_threshold_bps(&self) -> Result<Option<u32>, Error> {
+        let cur = self.cursor.table_slice_by_index(13usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn allow_abstain(&self) -> Result<Option<u8>, Error> {
+        let cur = self.cursor.table_slice_by_index(14usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn candidate_records(&self) -> Result<Option<CandidateVec>, Error> {
+        let cur = self.cursor.table_slice_by_index(15usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn version(&self) -> Result<Option<u8>, Error> {
+        let cur = self.cursor.table_slice_by_index(16usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn proposer_bond(&self) -> Result<Option<u64>, Error> {
+        let cur = self.cursor.table_slice_by_index(17usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn bond_forfeit_lock_hash(&self) -> Result<Option<[u8; 32usize]>, Error> {
+        let cur = self.cursor.table_slice_by_index(18usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn closure_policy(&self) -> Result<Option<u8>, Error> {
+        let cur = self.cursor.table_slice_by_index(19usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn committee(&self) -> Result<Option<Byte32Vec>, Error> {
+        let cur = self.cursor.table_slice_by_index(20usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn committee_threshold(&self) -> Result<Option<u8>, Error> {
+        let cur = self.cursor.table_slice_by_index(21usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn eligibility_mode(&self) -> Result<Option<u8>, Error> {
+        let cur = self.cursor.table_slice_by_index(22usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn snapshot_block_hash(&self) -> Result<Option<[u8; 32usize]>, Error> {
+        let cur = self.cursor.table_slice_by_index(23usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn deposit_type_hash(&self) -> Result<Option<[u8; 32usize]>, Error> {
+        let cur = self.cursor.table_slice_by_index(24usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn escrow_lock_code_hash(&self) -> Result<Option<[u8; 32usize]>, Error> {
+        let cur = self.cursor.table_slice_by_index(25usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn escrow_min_epochs(&self) -> Result<Option<u64>, Error> {
+        let cur = self.cursor.table_slice_by_index(26usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn escrow_max_epochs(&self) -> Result<Option<u64>, Error> {
+        let cur = self.cursor.table_slice_by_index(27usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn escrow_lock_hash_type(&self) -> Result<Option<u8>, Error> {
+        let cur = self.cursor.table_slice_by_index(28usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(cur.try_into()?))
+        }
+    }
+}
+impl VoteMeta {
+    pub fn verify(&self, compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_table(29usize, compatible)?;
+        let val = self.smt_root_hash()?;
+        if val.is_some() {
+            let val = val.unwrap();
+            Byte32::from(Cursor::try_from(val)?).verify(compatible)?;
+        }
+        self.candidates()?.verify(compatible)?;
+        let val = self.tally_type_hash()?;
+        if val.is_some() {
+            let val = val.unwrap();
+            Byte32::from(Cursor::try_from(val)?).verify(compatible)?;
+        }
+        let val = self.candidate_records()?;
+        if val.is_some() {
+            let val = val.unwrap();
+            val.verify(compatible)?;
+        }
+        let val = self.bond_forfeit_lock_hash()?;
+        if val.is_some() {
+            let val = val.unwrap();
+            Byte32::from(Cursor::try_from(val)?).verify(compatible)?;
+        }
+        let val = self.snapshot_block_hash()?;
+        if val.is_some() {
+            let val = val.unwrap();
+            Byte32::from(Cursor::try_from(val)?).verify(compatible)?;
+        }
+        let val = self.deposit_type_hash()?;
+        if val.is_some() {
+            let val = val.unwrap();
+            Byte32::from(Cursor::try_from(val)?).verify(compatible)?;
+        }
+        let val = self.escrow_lock_code_hash()?;
+        if val.is_some() {
+            let val = val.unwrap();
+            Byte32::from(Cursor::try_from(val)?).verify(compatible)?;
+        }
+        Ok(())
+    }
+}
+#[derive(Clone)]
+pub struct VoteProof {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for VoteProof {
+    fn from(cursor: Cursor) -> Self {
+        VoteProof { cursor }
+    }
+}
+impl VoteProof {
+    pub fn lock_script_hash(&self) -> Result<[u8; 32usize], Error> {
+        let cur = self.cursor.table_slice_by_index(0usize)?;
+        cur.try_into()
+    }
+}
+impl VoteProof {
+    pub fn smt_proof(&self) -> Result<Cursor, Error> {
+        let cur = self.cursor.table_slice_by_index(1usize)?;
+        cur.convert_to_rawbytes()
+    }
+}
+impl VoteProof {
+    pub fn verify(&self, compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_table(2usize, compatible)?;
+        Byte32::from(Cursor::try_from(self.lock_script_hash()?)?).verify(compatible)?;
+        Ok(())
+    }
+}
+#[derive(Clone)]
+pub struct Tally {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for Tally {
+    fn from(cursor: Cursor) -> Self {
+        Tally { cursor }
+    }
+}
+impl Tally {
+    pub fn session_id(&self) -> Result<[u8; 20usize], Error> {
+        let cur = self.cursor.table_slice_by_index(0usize)?;
+        cur.try_into()
+    }
+}
+impl Tally {
+    pub fn counts(&self) -> Result<Uint64Vec, Error> {
+        let cur = self.cursor.table_slice_by_index(1usize)?;
+        Ok(cur.into())
+    }
+}
+impl Tally {
+    pub fn turnout(&self) -> Result<u64, Error> {
+        let cur = self.cursor.table_slice_by_index(2usize)?;
+        cur.try_into()
+    }
+}
+impl Tally {
+    pub fn abstain(&self) -> Result<u64, Error> {
+        let cur = self.cursor.table_slice_by_index(3usize)?;
+        cur.try_into()
+    }
+}
+impl Tally {
+    pub fn voted_root(&self) -> Result<[u8; 32usize], Error> {
+        let cur = self.cursor.table_slice_by_index(4usize)?;
+        cur.try_into()
+    }
+}
+impl Tally {
+    pub fn verify(&self, compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_table(5usize, compatible)?;
+        Byte20::from(Cursor::try_from(self.session_id()?)?).verify(compatible)?;
+        Byte32::from(Cursor::try_from(self.voted_root()?)?).verify(compatible)?;
+        Ok(())
+    }
+}
+#[derive(Clone)]
+pub struct VoteResult {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for VoteResult {
+    fn from(cursor: Cursor) -> Self {
+        VoteResult { cursor }
+    }
+}
+impl VoteResult {
+    pub fn session_id(&self) -> Result<[u8; 20usize], Error> {
+        let cur = self.cursor.table_slice_by_index(0usize)?;
+        cur.try_into()
+    }
+}
+impl VoteResult {
+    pub fn meta_hash(&self) -> Result<[u8; 32usize], Error> {
+        let cur = self.cursor.table_slice_by_index(1usize)?;
+        cur.try_into()
+    }
+}
+impl VoteResult {
+    pub fn winners(&self) -> Result<u32, Error> {
+        let cur = self.cursor.table_slice_by_index(2usize)?;
+        cur.try_into()
+    }
+}
+impl VoteResult {
+    pub fn counts(&self) -> Result<Uint64Vec, Error> {
+        let cur = self.cursor.table_slice_by_index(3usize)?;
+        Ok(cur.into())
+    }
+}
+impl VoteResult {
+    pub fn turnout(&self) -> Result<u64, Error> {
+        let cur = self.cursor.table_slice_by_index(4usize)?;
+        cur.try_into()
+    }
+}
+impl VoteResult {
+    pub fn abstain(&self) -> Result<u64, Error> {
+        let cur = self.cursor.table_slice_by_index(5usize)?;
+        cur.try_into()
+    }
+}
+impl VoteResult {
+    pub fn quorum_reached(&self) -> Result<u8, Error> {
+        let cur = self.cursor.table_slice_by_index(6usize)?;
+        cur.try_into()
+    }
+}
+impl VoteResult {
+    pub fn passed(&self) -> Result<u8, Error> {
+        let cur = self.cursor.table_slice_by_index(7usize)?;
+        cur.try_into()
+    }
+}
+impl VoteResult {
+    pub fn verify(&self, compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_table(8usize, compatible)?;
+        Byte20::from(Cursor::try_from(self.session_id()?)?).verify(compatible)?;
+        Byte32::from(Cursor::try_from(self.meta_hash()?)?).verify(compatible)?;
+        Ok(())
+    }
+}
+#[derive(Clone)]
+pub struct WitnessArgs {
+    pub cursor: Cursor,
+}
+impl From<Cursor> for WitnessArgs {
+    fn from(cursor: Cursor) -> Self {
+        WitnessArgs { cursor }
+    }
+}
+impl WitnessArgs {
+    pub fn lock(&self) -> Result<Option<Cursor>, Error> {
+        let cur = self.cursor.table_slice_by_index(0usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            let cur = cur.convert_to_rawbytes()?;
+            Ok(Some(cur.into()))
+        }
+    }
+}
+impl WitnessArgs {
+    pub fn input_type(&self) -> Result<Option<Cursor>, Error> {
+        let cur = self.cursor.table_slice_by_index(1usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            let cur = cur.convert_to_rawbytes()?;
+            Ok(Some(cur.into()))
+        }
+    }
+}
+impl WitnessArgs {
+    pub fn output_type(&self) -> Result<Option<Cursor>, Error> {
+        let cur = self.cursor.table_slice_by_index(2usize)?;
+        if cur.option_is_none() {
+            Ok(None)
+        } else {
+            let cur = cur.convert_to_rawbytes()?;
+            Ok(Some(cur.into()))
+        }
+    }
+}
+impl WitnessArgs {
+    pub fn verify(&self, compatible: bool) -> Result<(), Error> {
+        self.cursor.verify_table(3usize, compatible)?;
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use blake2b_ref::{Blake2b, Blake2bBuilder};
-use sparse_merkle_tree::{H256, traits::Hasher};
+use sparse_merkle_tree::{traits::Hasher, H256};
 
 const BLAKE2B_KEY: &[u8] = &[];
 const BLAKE2B_LEN: usize = 32;
@@ -0,0 +1 @@
+ckb_std::entry_simulator!(ckb_dao_vote::program_entry);
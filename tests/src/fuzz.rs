@@ -0,0 +1,280 @@
+// Property tests feeding arbitrary molecule data and transaction shapes to the
+// type script. Whatever the input, verification must either pass or fail with
+// one of the codes in `ErrorCode`, never with a panic or a VM fault.
+//
+// Under the `native-simulator` feature the type script runs natively, which
+// also surfaces panics with a backtrace. PROPTEST_CASES raises the number of
+// cases, e.g. PROPTEST_CASES=10000 cargo test -p tests fuzz_
+use crate::{
+    molecules::{VoteMeta, VoteProof, WitnessArgs},
+    script_error_code,
+};
+use ckb_dao_vote_types::{error::ErrorCode, hash::blake160};
+use ckb_testtool::{
+    builtin::ALWAYS_SUCCESS,
+    ckb_error::Error,
+    ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*},
+    context::Context,
+};
+use proptest::prelude::*;
+use serde_molecule::to_vec;
+
+const MAX_CYCLES: u64 = 100_000_000;
+
+#[derive(Clone, Debug)]
+enum Args {
+    Session,
+    Guarded(u64),
+    Raw(Vec<u8>),
+}
+
+#[derive(Clone, Debug)]
+struct Case {
+    meta_data: Vec<u8>,
+    args: Args,
+    // data of the vote cells, one per output
+    ballots: Vec<Vec<u8>>,
+    witnesses: Vec<Vec<u8>>,
+    // consumes a vote cell with the given data
+    consumed: Option<Vec<u8>>,
+    since: u64,
+    unrelated_outputs: usize,
+}
+
+// Replaces bytes at random positions and optionally truncates the result.
+fn mutate(bytes: Vec<u8>) -> impl Strategy<Value = Vec<u8>> {
+    let len = bytes.len();
+    (
+        prop::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 0..4),
+        prop::option::of(0..=len),
+    )
+        .prop_map(move |(edits, truncate)| {
+            let mut bytes = bytes.clone();
+            if !bytes.is_empty() {
+                for (index, byte) in edits {
+                    let index = index.index(bytes.len());
+                    bytes[index] = byte;
+                }
+            }
+            if let Some(len) = truncate {
+                bytes.truncate(len);
+            }
+            bytes
+        })
+}
+
+fn vote_meta() -> impl Strategy<Value = Vec<u8>> {
+    (
+        prop::collection::vec(prop::collection::vec(any::<u8>(), 0..4), 0..34),
+        prop::option::of(any::<[u8; 32]>()),
+        any::<u64>(),
+        any::<u64>(),
+        prop::option::of(0u8..4),
+        prop::option::of(0u8..4),
+        prop::option::of(any::<u64>()),
+        prop::option::of(any::<u64>()),
+    )
+        .prop_map(
+            |(
+                candidates,
+                smt_root_hash,
+                start_time,
+                end_time,
+                version,
+                weight_mode,
+                min_vote_capacity,
+                max_vote_capacity,
+            )| {
+                let vote_meta = VoteMeta {
+                    smt_root_hash,
+                    candidates,
+                    start_time,
+                    end_time,
+                    version,
+                    weight_mode,
+                    min_vote_capacity,
+                    max_vote_capacity,
+                    ..Default::default()
+                };
+                to_vec(&vote_meta, false).unwrap()
+            },
+        )
+}
+
+fn meta_data() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        prop::collection::vec(any::<u8>(), 0..256),
+        vote_meta(),
+        vote_meta().prop_flat_map(mutate),
+    ]
+}
+
+fn witness() -> impl Strategy<Value = Vec<u8>> {
+    let vote_proof = (any::<[u8; 32]>(), prop::collection::vec(any::<u8>(), 0..64)).prop_map(
+        |(lock_script_hash, smt_proof)| {
+            to_vec(
+                &VoteProof {
+                    lock_script_hash,
+                    smt_proof,
+                },
+                false,
+            )
+            .unwrap()
+        },
+    );
+    let output_type = prop_oneof![prop::collection::vec(any::<u8>(), 0..128), vote_proof];
+    let witness_args = (
+        prop::option::of(prop::collection::vec(any::<u8>(), 0..16)),
+        prop::option::of(output_type),
+    )
+        .prop_map(|(lock, output_type)| {
+            to_vec(
+                &WitnessArgs {
+                    lock,
+                    input_type: None,
+                    output_type,
+                },
+                false,
+            )
+            .unwrap()
+        });
+    prop_oneof![
+        prop::collection::vec(any::<u8>(), 0..128),
+        witness_args.clone(),
+        witness_args.prop_flat_map(mutate),
+    ]
+}
+
+fn ballot() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        prop::collection::vec(any::<u8>(), 0..16),
+        any::<u32>().prop_map(|choices| choices.to_le_bytes().to_vec()),
+        (any::<u32>(), any::<u64>()).prop_map(|(choices, weight)| {
+            let mut data = choices.to_le_bytes().to_vec();
+            data.extend_from_slice(&weight.to_le_bytes());
+            data
+        }),
+    ]
+}
+
+fn case() -> impl Strategy<Value = Case> {
+    (
+        meta_data(),
+        prop_oneof![
+            Just(Args::Session),
+            any::<u64>().prop_map(Args::Guarded),
+            prop::collection::vec(any::<u8>(), 0..40).prop_map(Args::Raw),
+        ],
+        prop::collection::vec(ballot(), 0..3),
+        prop::collection::vec(witness(), 0..4),
+        prop::option::of(ballot()),
+        prop_oneof![Just(0), any::<u64>()],
+        0usize..3,
+    )
+        .prop_map(
+            |(meta_data, args, ballots, witnesses, consumed, since, unrelated_outputs)| Case {
+                meta_data,
+                args,
+                ballots,
+                witnesses,
+                consumed,
+                since,
+                unrelated_outputs,
+            },
+        )
+}
+
+fn run(case: &Case) -> Result<(), Error> {
+    let mut context = Context::default();
+    let out_point = context.deploy_cell_by_name("ckb-dao-vote");
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let always_success_script = context
+        .build_script(&always_success_out_point, Bytes::new())
+        .unwrap();
+    let meta_out_point = context.deploy_cell(Bytes::from(case.meta_data.clone()));
+
+    let session_id = blake160(meta_out_point.as_slice());
+    let args = match &case.args {
+        Args::Session => session_id.to_vec(),
+        Args::Guarded(end_time) => [&session_id[..], &end_time.to_le_bytes()].concat(),
+        Args::Raw(args) => args.clone(),
+    };
+    let type_script = context.build_script(&out_point, Bytes::from(args)).unwrap();
+
+    let plain_output = CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .lock(always_success_script.clone())
+        .build();
+    let vote_output = plain_output
+        .clone()
+        .as_builder()
+        .type_(Some(type_script).pack())
+        .build();
+    let input_out_point = match &case.consumed {
+        Some(data) => context.create_cell(vote_output.clone(), Bytes::from(data.clone())),
+        None => context.create_cell(plain_output.clone(), Bytes::new()),
+    };
+
+    let mut tx = TransactionBuilder::default()
+        .cell_dep(
+            CellDep::new_builder()
+                .out_point(meta_out_point)
+                .dep_type(0u8.into())
+                .build(),
+        )
+        .input(
+            CellInput::new_builder()
+                .previous_output(input_out_point)
+                .since(case.since.pack())
+                .build(),
+        );
+    for ballot in &case.ballots {
+        tx = tx
+            .output(vote_output.clone())
+            .output_data(Bytes::from(ballot.clone()).pack());
+    }
+    for _ in 0..case.unrelated_outputs {
+        tx = tx
+            .output(plain_output.clone())
+            .output_data(Bytes::new().pack());
+    }
+    for witness in &case.witnesses {
+        tx = tx.witness(Bytes::from(witness.clone()).pack());
+    }
+    let tx = context.complete_tx(tx.build());
+    context.verify_tx(&tx, MAX_CYCLES).map(|_| ())
+}
+
+proptest! {
+    #[test]
+    fn fuzz_vote_meta_reader(data in meta_data()) {
+        // the lazy reader must reject malformed data without panicking
+        let vote_meta = ckb_dao_vote_types::molecules::vote::VoteMeta::from(
+            molecule::lazy_reader::Cursor::from(data),
+        );
+        if vote_meta.verify_compatible().is_ok() {
+            if let Ok(candidates) = vote_meta.candidates() {
+                for index in 0..candidates.len().unwrap_or(0) {
+                    let _ = candidates.get(index);
+                }
+            }
+            let _ = vote_meta.start_time();
+            let _ = vote_meta.end_time();
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn fuzz_type_script(case in case()) {
+        if let Err(err) = run(&case) {
+            let code = script_error_code(&err);
+            prop_assert!(
+                code.and_then(ErrorCode::from_code).is_some(),
+                "undefined failure {} for {:?}",
+                err,
+                case
+            );
+        }
+    }
+}
@@ -0,0 +1,618 @@
+// Randomized vote creation and consumption transactions, over several sessions,
+// voters and unrelated cells, with random faults. A reference model written
+// from docs/ckb-dao-vote.md predicts whether each transaction passes, and the
+// prediction is checked against `Context::verify_tx`.
+//
+// The model covers the open, SMT restricted and guarded sessions, capacity
+// ranges, abstain and version 2 ballots. Tally cells, bonds and eligibility
+// snapshots are left to the scheme tests in tests.rs.
+use crate::{
+    molecules::{VoteMeta, VoteProof, WitnessArgs},
+    script_error_code,
+    tests::CkbSMT,
+};
+use ckb_dao_vote_types::{
+    constants::{SMT_VALUE, WEIGHT_MODE_CAPACITY},
+    error::ErrorCode,
+    hash::blake160,
+};
+use ckb_testtool::{
+    builtin::ALWAYS_SUCCESS,
+    ckb_types::{
+        bytes::Bytes,
+        core::{HeaderBuilder, TransactionBuilder, TransactionView},
+        packed::*,
+        prelude::*,
+    },
+    context::Context,
+};
+use proptest::prelude::*;
+use serde_molecule::to_vec;
+
+const MAX_CYCLES: u64 = 100_000_000;
+const VOTERS: usize = 6;
+
+#[derive(Clone, Debug)]
+struct SessionSpec {
+    candidates: usize,
+    allow_abstain: bool,
+    min_capacity: Option<u64>,
+    max_capacity: Option<u64>,
+    // guarded until the absolute block number
+    guarded_until: Option<u64>,
+    // eligible voters of an SMT restricted session
+    eligible: Option<[bool; VOTERS]>,
+    version_two: bool,
+    weight_by_capacity: bool,
+}
+
+#[derive(Clone, Debug)]
+enum WitnessSpec {
+    Proof,
+    Empty,
+    NoOutputType,
+}
+
+#[derive(Clone, Debug)]
+struct BallotSpec {
+    session: prop::sample::Index,
+    voter: usize,
+    // the lock hash in the vote proof belongs to another voter
+    claimed: Option<usize>,
+    voter_in_inputs: bool,
+    bits: u32,
+    // keep bits beyond the candidates
+    overflow: bool,
+    capacity: u64,
+    // added to or removed from the ballot data
+    length_delta: i8,
+    wrong_weight: bool,
+    witness: WitnessSpec,
+    valid_smt_proof: bool,
+    // guarded args for an unguarded session or the reverse
+    wrong_args: bool,
+}
+
+#[derive(Clone, Debug)]
+struct VoteCase {
+    sessions: Vec<SessionSpec>,
+    ballots: Vec<BallotSpec>,
+    // sessions whose vote meta cell is in cell deps
+    meta_deps: Vec<bool>,
+    // positions of unrelated outputs among the vote cells
+    unrelated_outputs: Vec<prop::sample::Index>,
+}
+
+#[derive(Clone, Debug)]
+struct ConsumedSpec {
+    session: prop::sample::Index,
+    // absolute block number since of the input
+    since: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+struct ConsumeCase {
+    sessions: Vec<SessionSpec>,
+    consumed: Vec<ConsumedSpec>,
+    // sessions whose vote meta cell is consumed too
+    consume_meta: Vec<bool>,
+    header_dep: Option<u64>,
+    // a vote cell is created in the same transaction
+    vote_output: bool,
+    unrelated_inputs: usize,
+}
+
+fn session_spec() -> impl Strategy<Value = SessionSpec> {
+    (
+        1usize..=8,
+        any::<bool>(),
+        prop::option::weighted(0.3, 100u64..800),
+        prop::option::weighted(0.3, 1200u64..2000),
+        prop::option::weighted(0.4, 10u64..100),
+        prop::option::weighted(0.4, any::<[bool; VOTERS]>()),
+        any::<bool>(),
+        any::<bool>(),
+    )
+        .prop_map(
+            |(
+                candidates,
+                allow_abstain,
+                min_capacity,
+                max_capacity,
+                guarded_until,
+                eligible,
+                version_two,
+                weight_by_capacity,
+            )| SessionSpec {
+                candidates,
+                allow_abstain,
+                min_capacity,
+                max_capacity,
+                guarded_until,
+                eligible,
+                version_two,
+                weight_by_capacity,
+            },
+        )
+}
+
+fn ballot_spec() -> impl Strategy<Value = BallotSpec> {
+    (
+        (
+            any::<prop::sample::Index>(),
+            0..VOTERS,
+            prop::option::weighted(0.05, 0..VOTERS),
+            prop::bool::weighted(0.95),
+            any::<u32>(),
+            prop::bool::weighted(0.1),
+        ),
+        (
+            0u64..2500,
+            prop_oneof![18 => Just(0i8), 1 => Just(-1i8), 1 => Just(1i8)],
+            prop::bool::weighted(0.05),
+            prop_oneof![
+                18 => Just(WitnessSpec::Proof),
+                1 => Just(WitnessSpec::Empty),
+                1 => Just(WitnessSpec::NoOutputType),
+            ],
+            prop::bool::weighted(0.95),
+            prop::bool::weighted(0.05),
+        ),
+    )
+        .prop_map(
+            |(
+                (session, voter, claimed, voter_in_inputs, bits, overflow),
+                (capacity, length_delta, wrong_weight, witness, valid_smt_proof, wrong_args),
+            )| BallotSpec {
+                session,
+                voter,
+                claimed,
+                voter_in_inputs,
+                bits,
+                overflow,
+                capacity,
+                length_delta,
+                wrong_weight,
+                witness,
+                valid_smt_proof,
+                wrong_args,
+            },
+        )
+}
+
+fn vote_case() -> impl Strategy<Value = VoteCase> {
+    (
+        prop::collection::vec(session_spec(), 1..4),
+        prop::collection::vec(ballot_spec(), 1..6),
+        prop::collection::vec(prop::bool::weighted(0.95), 3),
+        prop::collection::vec(any::<prop::sample::Index>(), 0..3),
+    )
+        .prop_map(
+            |(sessions, ballots, meta_deps, unrelated_outputs)| VoteCase {
+                sessions,
+                ballots,
+                meta_deps,
+                unrelated_outputs,
+            },
+        )
+}
+
+fn consume_case() -> impl Strategy<Value = ConsumeCase> {
+    (
+        prop::collection::vec(session_spec(), 1..4),
+        prop::collection::vec(
+            (any::<prop::sample::Index>(), prop::option::of(0u64..120))
+                .prop_map(|(session, since)| ConsumedSpec { session, since }),
+            1..5,
+        ),
+        prop::collection::vec(prop::bool::weighted(0.1), 3),
+        prop::option::weighted(0.2, 0u64..120),
+        prop::bool::weighted(0.05),
+        0usize..3,
+    )
+        .prop_map(
+            |(sessions, consumed, consume_meta, header_dep, vote_output, unrelated_inputs)| {
+                ConsumeCase {
+                    sessions,
+                    consumed,
+                    consume_meta,
+                    header_dep,
+                    vote_output,
+                    unrelated_inputs,
+                }
+            },
+        )
+}
+
+impl SessionSpec {
+    fn ballot_len(&self) -> usize {
+        if self.version_two {
+            12
+        } else {
+            4
+        }
+    }
+
+    // a valid ballot for the first candidate
+    fn ballot(&self, capacity: u64) -> Vec<u8> {
+        let mut data = 1u32.to_le_bytes().to_vec();
+        if self.version_two {
+            let weight = if self.weight_by_capacity { capacity } else { 1 };
+            data.extend_from_slice(&weight.to_le_bytes());
+        }
+        data
+    }
+}
+
+impl BallotSpec {
+    fn choices(&self, session: &SessionSpec) -> u32 {
+        if self.overflow {
+            self.bits
+        } else {
+            self.bits & ((1u32 << session.candidates) - 1)
+        }
+    }
+
+    fn claimed(&self) -> usize {
+        self.claimed.unwrap_or(self.voter)
+    }
+
+    fn weight(&self, session: &SessionSpec) -> u64 {
+        let weight = if session.weight_by_capacity {
+            self.capacity
+        } else {
+            1
+        };
+        if self.wrong_weight {
+            weight.wrapping_add(1)
+        } else {
+            weight
+        }
+    }
+
+    fn data(&self, session: &SessionSpec) -> Vec<u8> {
+        let mut data = self.choices(session).to_le_bytes().to_vec();
+        if session.version_two {
+            data.extend_from_slice(&self.weight(session).to_le_bytes());
+        }
+        match self.length_delta {
+            -1 => {
+                data.pop();
+            }
+            1 => data.push(0),
+            _ => {}
+        }
+        data
+    }
+}
+
+// Reference model of vote creation, following "Validation Procedure".
+fn model_vote(case: &VoteCase) -> bool {
+    let voters_in_inputs: Vec<usize> = case
+        .ballots
+        .iter()
+        .filter(|ballot| ballot.voter_in_inputs)
+        .map(|ballot| ballot.voter)
+        .collect();
+    case.ballots.iter().all(|ballot| {
+        let index = ballot.session.index(case.sessions.len());
+        let session = &case.sessions[index];
+        let claimed = ballot.claimed();
+        let data = ballot.data(session);
+        let choices = ballot.choices(session);
+        // step 3
+        case.meta_deps[index]
+            && !ballot.wrong_args
+            // witness format
+            && matches!(ballot.witness, WitnessSpec::Proof)
+            // step 4
+            && session
+                .eligible
+                .is_none_or(|eligible| eligible[claimed] && ballot.valid_smt_proof)
+            // step 5
+            && voters_in_inputs.contains(&claimed)
+            // step 6
+            && data.len() == session.ballot_len()
+            && (choices != 0 || session.allow_abstain)
+            && (32 - choices.leading_zeros() as usize) <= session.candidates
+            && session.min_capacity.is_none_or(|min| ballot.capacity >= min)
+            && session.max_capacity.is_none_or(|max| ballot.capacity <= max)
+            && (!session.version_two || !ballot.wrong_weight)
+    })
+}
+
+// Reference model of vote consumption, following step 2.
+fn model_consume(case: &ConsumeCase) -> bool {
+    if case.vote_output {
+        return false;
+    }
+    case.sessions.iter().enumerate().all(|(index, session)| {
+        let Some(end) = session.guarded_until else {
+            return true;
+        };
+        let mut consumed = case
+            .consumed
+            .iter()
+            .filter(|input| input.session.index(case.sessions.len()) == index)
+            .peekable();
+        consumed.peek().is_none()
+            || case.consume_meta[index]
+            || case.header_dep.is_some_and(|number| number >= end)
+            || consumed.all(|input| input.since.is_some_and(|since| since >= end))
+    })
+}
+
+struct Session {
+    spec: SessionSpec,
+    meta_out_point: OutPoint,
+    session_id: [u8; 20],
+    tree: CkbSMT,
+}
+
+struct World {
+    context: Context,
+    vote_script: Script,
+    always_success_script: Script,
+    sessions: Vec<Session>,
+}
+
+impl World {
+    fn new(specs: &[SessionSpec]) -> Self {
+        let mut context = Context::default();
+        let vote_out_point = context.deploy_cell_by_name("ckb-dao-vote");
+        let vote_script = context.build_script(&vote_out_point, Bytes::new()).unwrap();
+        let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+        let always_success_script = context
+            .build_script(&always_success_out_point, Bytes::new())
+            .unwrap();
+        let mut world = Self {
+            context,
+            vote_script,
+            always_success_script,
+            sessions: Vec::new(),
+        };
+        for spec in specs {
+            let mut tree = CkbSMT::default();
+            if let Some(eligible) = &spec.eligible {
+                for voter in (0..VOTERS).filter(|voter| eligible[*voter]) {
+                    tree.update(world.voter_hash(voter).into(), SMT_VALUE.into())
+                        .unwrap();
+                }
+            }
+            let vote_meta = VoteMeta {
+                smt_root_hash: spec.eligible.map(|_| tree.root().clone().into()),
+                candidates: (0..spec.candidates).map(|i| vec![i as u8]).collect(),
+                start_time: 0,
+                end_time: spec.guarded_until.unwrap_or(0),
+                extra: None,
+                min_vote_capacity: spec.min_capacity,
+                max_vote_capacity: spec.max_capacity,
+                guarded_consumption: spec.guarded_until.map(|_| 1),
+                weight_mode: spec.weight_by_capacity.then_some(WEIGHT_MODE_CAPACITY),
+                allow_abstain: spec.allow_abstain.then_some(1),
+                version: spec.version_two.then_some(2),
+                ..Default::default()
+            };
+            let meta_out_point = world.context.create_cell(
+                CellOutput::new_builder()
+                    .capacity(1000u64.pack())
+                    .lock(world.always_success_script.clone())
+                    .build(),
+                Bytes::from(to_vec(&vote_meta, false).unwrap()),
+            );
+            world.sessions.push(Session {
+                spec: spec.clone(),
+                session_id: blake160(meta_out_point.as_slice()),
+                meta_out_point,
+                tree,
+            });
+        }
+        world
+    }
+
+    fn voter_lock(&self, voter: usize) -> Script {
+        self.always_success_script
+            .clone()
+            .as_builder()
+            .args(Bytes::from(vec![voter as u8]).pack())
+            .build()
+    }
+
+    fn voter_hash(&self, voter: usize) -> [u8; 32] {
+        self.voter_lock(voter)
+            .calc_script_hash()
+            .as_slice()
+            .try_into()
+            .unwrap()
+    }
+
+    fn vote_type(&self, session: &Session, guarded: bool) -> Script {
+        let mut args = session.session_id.to_vec();
+        if guarded {
+            args.extend_from_slice(&session.spec.guarded_until.unwrap_or(0).to_le_bytes());
+        }
+        self.vote_script
+            .clone()
+            .as_builder()
+            .args(Bytes::from(args).pack())
+            .build()
+    }
+
+    fn plain_output(&self, capacity: u64) -> CellOutput {
+        CellOutput::new_builder()
+            .capacity(capacity.pack())
+            .lock(self.always_success_script.clone())
+            .build()
+    }
+
+    fn vote_output(&self, session: &Session, guarded: bool, capacity: u64) -> CellOutput {
+        self.plain_output(capacity)
+            .as_builder()
+            .type_(Some(self.vote_type(session, guarded)).pack())
+            .build()
+    }
+
+    fn meta_dep(&self, session: &Session) -> CellDep {
+        CellDep::new_builder()
+            .out_point(session.meta_out_point.clone())
+            .dep_type(0u8.into())
+            .build()
+    }
+
+    fn witness(&self, session: &Session, ballot: &BallotSpec) -> Bytes {
+        let claimed = self.voter_hash(ballot.claimed());
+        let smt_proof = if ballot.valid_smt_proof {
+            session
+                .tree
+                .merkle_proof(vec![claimed.into()])
+                .unwrap()
+                .compile(vec![claimed.into()])
+                .unwrap()
+                .0
+        } else {
+            vec![0]
+        };
+        let vote_proof = VoteProof {
+            lock_script_hash: claimed,
+            smt_proof,
+        };
+        let output_type = match ballot.witness {
+            WitnessSpec::Proof => Some(to_vec(&vote_proof, false).unwrap()),
+            WitnessSpec::NoOutputType => None,
+            WitnessSpec::Empty => return Bytes::new(),
+        };
+        let witness_args = WitnessArgs {
+            lock: None,
+            input_type: None,
+            output_type,
+        };
+        Bytes::from(to_vec(&witness_args, false).unwrap())
+    }
+
+    fn vote_tx(&mut self, case: &VoteCase) -> TransactionView {
+        let mut outputs = Vec::new();
+        let mut witnesses = Vec::new();
+        for ballot in &case.ballots {
+            let session = &self.sessions[ballot.session.index(self.sessions.len())];
+            let guarded = session.spec.guarded_until.is_some() != ballot.wrong_args;
+            let output = self.vote_output(session, guarded, ballot.capacity);
+            outputs.push((output, Bytes::from(ballot.data(&session.spec))));
+            witnesses.push(self.witness(session, ballot));
+        }
+        for position in &case.unrelated_outputs {
+            let position = position.index(outputs.len() + 1);
+            outputs.insert(position, (self.plain_output(500), Bytes::new()));
+            witnesses.insert(position, Bytes::new());
+        }
+
+        let mut tx = TransactionBuilder::default();
+        for (session, included) in self.sessions.iter().zip(&case.meta_deps) {
+            if *included {
+                tx = tx.cell_dep(self.meta_dep(session));
+            }
+        }
+        let mut voters: Vec<usize> = case
+            .ballots
+            .iter()
+            .filter(|ballot| ballot.voter_in_inputs)
+            .map(|ballot| ballot.voter)
+            .collect();
+        voters.sort_unstable();
+        voters.dedup();
+        // an unrelated input, so that no transaction is without inputs
+        let mut locks = vec![self.always_success_script.clone()];
+        locks.extend(voters.into_iter().map(|voter| self.voter_lock(voter)));
+        for lock in locks {
+            let out_point = self.context.create_cell(
+                CellOutput::new_builder()
+                    .capacity(10_000u64.pack())
+                    .lock(lock)
+                    .build(),
+                Bytes::new(),
+            );
+            tx = tx.input(CellInput::new_builder().previous_output(out_point).build());
+        }
+        for (output, data) in outputs {
+            tx = tx.output(output).output_data(data.pack());
+        }
+        for witness in witnesses {
+            tx = tx.witness(witness.pack());
+        }
+        self.context.complete_tx(tx.build())
+    }
+
+    fn consume_tx(&mut self, case: &ConsumeCase) -> TransactionView {
+        let mut tx = TransactionBuilder::default();
+        for _ in 0..case.unrelated_inputs {
+            let out_point = self
+                .context
+                .create_cell(self.plain_output(500), Bytes::new());
+            tx = tx.input(CellInput::new_builder().previous_output(out_point).build());
+        }
+        for input in &case.consumed {
+            let session = &self.sessions[input.session.index(self.sessions.len())];
+            let output = self.vote_output(session, session.spec.guarded_until.is_some(), 1000);
+            let data = session.spec.ballot(1000);
+            let out_point = self.context.create_cell(output, Bytes::from(data));
+            tx = tx.input(
+                CellInput::new_builder()
+                    .previous_output(out_point)
+                    .since(input.since.unwrap_or(0).pack())
+                    .build(),
+            );
+        }
+        for (session, consumed) in self.sessions.iter().zip(&case.consume_meta) {
+            if *consumed {
+                tx = tx.input(
+                    CellInput::new_builder()
+                        .previous_output(session.meta_out_point.clone())
+                        .build(),
+                );
+            }
+        }
+        if let Some(number) = case.header_dep {
+            let header = HeaderBuilder::default().number(number.pack()).build();
+            self.context.insert_header(header.clone());
+            tx = tx.header_dep(header.hash());
+        }
+        if case.vote_output {
+            let session = &self.sessions[0];
+            let output = self.vote_output(session, session.spec.guarded_until.is_some(), 1000);
+            let data = session.spec.ballot(1000);
+            tx = tx.output(output).output_data(Bytes::from(data).pack());
+        }
+        self.context.complete_tx(tx.build())
+    }
+}
+
+fn check(context: &Context, tx: &TransactionView, expected: bool) -> Result<(), TestCaseError> {
+    match context.verify_tx(tx, MAX_CYCLES) {
+        Ok(_) => prop_assert!(expected, "model predicted a failure"),
+        Err(err) => {
+            prop_assert!(!expected, "model predicted a pass: {}", err);
+            let code = script_error_code(&err);
+            prop_assert!(
+                code.and_then(ErrorCode::from_code).is_some(),
+                "undefined failure {}",
+                err
+            );
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn generated_vote_tx(case in vote_case()) {
+        let mut world = World::new(&case.sessions);
+        let tx = world.vote_tx(&case);
+        check(&world.context, &tx, model_vote(&case))?;
+    }
+
+    #[test]
+    fn generated_consume_tx(case in consume_case()) {
+        let mut world = World::new(&case.sessions);
+        let tx = world.consume_tx(&case);
+        check(&world.context, &tx, model_consume(&case))?;
+    }
+}
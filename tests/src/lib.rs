@@ -11,11 +11,14 @@ use std::fs;
 use std::path::PathBuf;
 use std::str::FromStr;
 
+#[cfg(test)]
+mod fuzz;
+#[cfg(test)]
+mod generator;
 #[cfg(test)]
 mod tests;
 
 pub mod molecules;
-pub mod smt_hasher;
 
 // The exact same Loader code from capsule's template, except that
 // now we use MODE as the environment variable
@@ -93,6 +96,18 @@ impl Loader {
     }
 }
 
+// Exit code of a failed script, from the message of a
+// `ScriptError::ValidationFailure`.
+pub fn script_error_code(err: &Error) -> Option<i8> {
+    let message = err.to_string();
+    let start = message.find("error code ")? + "error code ".len();
+    let code: String = message[start..]
+        .chars()
+        .take_while(|c| *c == '-' || c.is_ascii_digit())
+        .collect();
+    code.parse().ok()
+}
+
 // This helper method runs Context::verify_tx, but in case error happens,
 // it also dumps current transaction to failed_txs folder.
 pub fn verify_and_dump_failed_tx(
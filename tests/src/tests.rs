@@ -1,27 +1,32 @@
 use crate::{
-    molecules::{VoteMeta, VoteProof, WitnessArgs},
+    molecules::{
+        Candidate, CandidateVec, LegacyVoteMeta, Tally, VoteMeta, VoteProof, VoteResult,
+        WitnessArgs,
+    },
+    script_error_code, Loader,
+};
+use ckb_dao_vote_types::{
+    constants::{META_ARGS, SMT_VALUE},
+    error::ErrorCode,
+    hash::blake160,
     smt_hasher::Blake2bHasher,
-    Loader,
 };
 use ckb_testtool::{
     builtin::ALWAYS_SUCCESS,
-    ckb_hash::blake2b_256,
-    ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*},
+    ckb_hash::{blake2b_256, new_blake2b},
+    ckb_types::{
+        bytes::Bytes,
+        core::{HeaderBuilder, ScriptHashType, TransactionBuilder},
+        packed::*,
+        prelude::*,
+    },
     context::Context,
 };
 use serde_molecule::to_vec;
 use sparse_merkle_tree::{default_store::DefaultStore, SparseMerkleTree, H256};
 
-const SMT_VALUE: [u8; 32] = [
-    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-];
-
 pub type CkbSMT = SparseMerkleTree<Blake2bHasher, H256, DefaultStore<H256>>;
 
-fn blake160(bytes: &[u8]) -> [u8; 20] {
-    blake2b_256(bytes)[0..20].try_into().unwrap()
-}
-
 #[test]
 fn test_open_vote() {
     // open vote doesn't require smt root hash
@@ -31,14 +36,14 @@ fn test_open_vote() {
         start_time: 0,
         end_time: 0,
         extra: None,
+        ..Default::default()
     };
     let vote_meta_bin = to_vec(&vote_meta, false).expect("serialize vote meta");
 
     let mut context = Context::default();
     let vote_meta_out_point = context.deploy_cell(vote_meta_bin.into());
 
-    let script_bin: Bytes = Loader::default().load_binary("ckb-dao-vote");
-    let out_point = context.deploy_cell(script_bin);
+    let out_point = context.deploy_cell_by_name("ckb-dao-vote");
 
     let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
 
@@ -110,8 +115,7 @@ fn test_open_vote() {
 fn test_consume_vote() {
     // no need to attach vote meta cell
     let mut context = Context::default();
-    let script_bin: Bytes = Loader::default().load_binary("ckb-dao-vote");
-    let out_point = context.deploy_cell(script_bin);
+    let out_point = context.deploy_cell_by_name("ckb-dao-vote");
 
     let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
 
@@ -151,180 +155,127 @@ fn test_consume_vote() {
     println!("consume cycles: {}", cycles);
 }
 
-pub(crate) enum TestScheme {
-    Normal,
-    Molecule,
-    WrongTxType,
-    WrongArgs,
-    NoMetaCell,
-    VerifySmtFail,
-    VerifySmtNotOn,
-    NoLockFound,
-    WrongVoteCandidate,
-    WrongVoteCandidateExceedLimit,
-    MultipleCandidates,
+// How a guarded vote consumption proves that the vote session has ended
+pub(crate) enum GuardProof {
+    Nothing,
+    Since,
+    SinceTooEarly,
+    HeaderDep,
+    HeaderDepTooEarly,
+    MetaCellConsumed,
+    ResultCellDep,
 }
 
-pub(crate) struct Config {
-    voter_count: usize,
-    candidate_count: usize,
-    test_scheme: TestScheme,
-}
+const GUARDED_END_BLOCK: u64 = 100;
 
-pub(crate) fn entry(config: &Config) {
-    let mut context = Context::default();
+// a relative block number since, which no transaction ever reaches
+const RELATIVE_END: u64 = (1 << 63) | 100;
 
-    // collect all voter's lock script hashes into SMT
+pub(crate) fn consume_guarded_vote(proof: GuardProof) {
+    let mut context = Context::default();
+    let out_point = context.deploy_cell_by_name("ckb-dao-vote");
     let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
-    let mut smt_tree = CkbSMT::default();
-    for i in 0..config.voter_count {
-        // make args different to represent different voters
-        let voter_lock_script = context
-            .build_script(&always_success_out_point, Bytes::from(vec![i as u8]))
-            .unwrap();
-        let script_hash = voter_lock_script.calc_script_hash();
-        let key: [u8; 32] = script_hash.as_slice().try_into().unwrap();
-        smt_tree
-            .update(key.into(), SMT_VALUE.clone().into())
-            .unwrap();
-    }
-    let smt_root_hash = match config.test_scheme {
-        TestScheme::VerifySmtNotOn => [0u8; 32],
-        _ => smt_tree.root().clone().into(),
-    };
-    let vote_meta = VoteMeta {
-        smt_root_hash: Some(smt_root_hash),
-        candidates: (0..config.candidate_count).map(|i| vec![i as u8]).collect(),
-        start_time: 0,
-        end_time: 0,
-        extra: None,
-    };
-    let vote_meta_bin = to_vec(&vote_meta, false).unwrap();
-
-    let vote_meta_bin = match config.test_scheme {
-        TestScheme::Molecule => vote_meta_bin.iter().map(|_| 0).collect(),
-        _ => vote_meta_bin,
-    };
-    let vote_meta_out_point = context.deploy_cell(vote_meta_bin.into());
-
-    let script_bin: Bytes = Loader::default().load_binary("ckb-dao-vote");
-    let out_point = context.deploy_cell(script_bin);
-
-    let args = match config.test_scheme {
-        TestScheme::NoMetaCell => [0u8; 20],
-        _ => blake160(vote_meta_out_point.as_slice()),
-    };
-    let args: Vec<u8> = match config.test_scheme {
-        TestScheme::WrongArgs => vec![0u8; 10],
-        _ => args.to_vec(),
-    };
-    let type_script = context.build_script(&out_point, Bytes::from(args)).unwrap();
     let always_success_script = context
         .build_script(&always_success_out_point, Bytes::new())
         .unwrap();
 
-    let mut inputs = vec![];
-    let mut outputs = vec![];
-    let mut outputs_data = vec![];
-    let mut witnesses = vec![];
-    for i in 0..config.voter_count {
-        // make args different to represent different voters
-        let voter_lock_script = context
-            .build_script(&always_success_out_point, Bytes::from(vec![i as u8]))
-            .unwrap();
-
-        let input_type_script = match config.test_scheme {
-            TestScheme::WrongTxType => Some(type_script.clone()).pack(),
-            _ => None::<Script>.pack(),
-        };
+    // the vote meta cell content is irrelevant for consumption
+    let meta_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(always_success_script.clone())
+            .build(),
+        Bytes::new(),
+    );
+    // end_time is an absolute block number since
+    let mut args = blake160(meta_out_point.as_slice()).to_vec();
+    args.extend_from_slice(&GUARDED_END_BLOCK.to_le_bytes());
+    let type_script = context.build_script(&out_point, Bytes::from(args)).unwrap();
 
-        let input_out_point = context.create_cell(
-            CellOutput::new_builder()
-                .capacity(1000u64.pack())
-                .lock(voter_lock_script.clone())
-                .type_(input_type_script)
-                .build(),
-            Bytes::new(),
-        );
-        inputs.push(
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(always_success_script.clone())
+            .type_(Some(type_script).pack())
+            .build(),
+        Bytes::from(vec![1, 0, 0, 0]),
+    );
+    let since: u64 = match proof {
+        GuardProof::Since => GUARDED_END_BLOCK,
+        GuardProof::SinceTooEarly => GUARDED_END_BLOCK - 1,
+        _ => 0,
+    };
+    let mut tx = TransactionBuilder::default()
+        .input(
             CellInput::new_builder()
                 .previous_output(input_out_point)
+                .since(since.pack())
                 .build(),
-        );
-        outputs.push(
+        )
+        .output(
             CellOutput::new_builder()
                 .capacity(500u64.pack())
                 .lock(always_success_script.clone())
-                .type_(Some(type_script.clone()).pack())
                 .build(),
-        );
-        // choice
-        match config.test_scheme {
-            TestScheme::WrongVoteCandidate => {
-                outputs_data.push(Bytes::from(vec![0, 0, 0, 1]).pack());
-            }
-            TestScheme::WrongVoteCandidateExceedLimit => {
-                let data: u32 = 1 << config.candidate_count;
-                outputs_data.push(Bytes::copy_from_slice(&data.to_le_bytes()).pack());
-            }
-            TestScheme::MultipleCandidates => {
-                // select all choices
-                let data: u32 = (1 << config.candidate_count) - 1;
-                outputs_data.push(Bytes::copy_from_slice(&data.to_le_bytes()).pack());
-            }
-            _ => {
-                outputs_data.push(Bytes::from(vec![1, 0, 0, 0]).pack());
-            }
+        )
+        .output_data(Bytes::new().pack());
+    match proof {
+        GuardProof::HeaderDep | GuardProof::HeaderDepTooEarly => {
+            let number = match proof {
+                GuardProof::HeaderDep => GUARDED_END_BLOCK,
+                _ => GUARDED_END_BLOCK - 1,
+            };
+            let header = HeaderBuilder::default().number(number.pack()).build();
+            context.insert_header(header.clone());
+            tx = tx.header_dep(header.hash());
         }
-
-        // witness
-        let key: [u8; 32] = voter_lock_script
-            .calc_script_hash()
-            .as_slice()
-            .try_into()
-            .unwrap();
-        let proof = smt_tree.merkle_proof(vec![key.into()]).unwrap();
-        let compiled_proof = proof.clone().compile(vec![key.into()]).unwrap();
-
-        let key = match config.test_scheme {
-            TestScheme::NoLockFound => [0u8; 32],
-            _ => key,
-        };
-        let smt_proof = match config.test_scheme {
-            TestScheme::VerifySmtFail => vec![0u8; 1],
-            _ => compiled_proof.0,
-        };
-        let vote_proof = VoteProof {
-            lock_script_hash: key,
-            smt_proof,
-        };
-
-        let witness_args = WitnessArgs {
-            lock: None,
-            input_type: None,
-            output_type: Some(to_vec(&vote_proof, false).unwrap()),
-        };
-        let witness_args = Bytes::from(to_vec(&witness_args, false).unwrap());
-        witnesses.push(witness_args.pack());
+        GuardProof::MetaCellConsumed => {
+            tx = tx.input(
+                CellInput::new_builder()
+                    .previous_output(meta_out_point)
+                    .build(),
+            );
+        }
+        GuardProof::ResultCellDep => {
+            let result_script = context
+                .build_script(&out_point, Bytes::from([&[42u8; 32][..], &[0]].concat()))
+                .unwrap();
+            let vote_result = VoteResult {
+                session_id: blake160(meta_out_point.as_slice()),
+                meta_hash: [0u8; 32],
+                winners: 1,
+                counts: vec![1],
+                turnout: 1,
+                abstain: 0,
+                quorum_reached: 1,
+                passed: 1,
+            };
+            let result_out_point = context.create_cell(
+                CellOutput::new_builder()
+                    .capacity(1000u64.pack())
+                    .lock(always_success_script.clone())
+                    .type_(Some(result_script).pack())
+                    .build(),
+                to_vec(&vote_result, false).unwrap().into(),
+            );
+            tx = tx.cell_dep(
+                CellDep::new_builder()
+                    .out_point(result_out_point)
+                    .dep_type(0u8.into())
+                    .build(),
+            );
+        }
+        _ => {}
     }
-    let vote_meta_cell_dep = CellDep::new_builder()
-        .out_point(vote_meta_out_point)
-        .dep_type(0u8.into())
-        .build();
-    let tx = TransactionBuilder::default()
-        .cell_dep(vote_meta_cell_dep)
-        .inputs(inputs)
-        .outputs(outputs)
-        .outputs_data(outputs_data)
-        .witnesses(witnesses)
-        .build();
-    let tx = context.complete_tx(tx);
+    let tx = context.complete_tx(tx.build());
 
     let result = context.verify_tx(&tx, 10_000_000);
-    match config.test_scheme {
-        TestScheme::Normal | TestScheme::MultipleCandidates => {
-            assert!(result.is_ok());
-            println!("consume cycles: {}", result.unwrap());
+    match proof {
+        GuardProof::Since
+        | GuardProof::HeaderDep
+        | GuardProof::MetaCellConsumed
+        | GuardProof::ResultCellDep => {
+            println!("consume cycles: {}", result.expect("pass verification"));
         }
         _ => {
             assert!(result.is_err());
@@ -333,109 +284,2778 @@ pub(crate) fn entry(config: &Config) {
 }
 
 #[test]
-fn test_one_vote() {
-    entry(&Config {
-        voter_count: 1,
-        candidate_count: 1,
-        test_scheme: TestScheme::Normal,
-    });
+fn test_consume_guarded_vote_without_proof() {
+    consume_guarded_vote(GuardProof::Nothing);
 }
 
 #[test]
-fn test_multiple_vote() {
-    entry(&Config {
-        voter_count: 3,
-        candidate_count: 5,
-        test_scheme: TestScheme::Normal,
-    });
+fn test_consume_guarded_vote_with_since() {
+    consume_guarded_vote(GuardProof::Since);
 }
 
 #[test]
-fn test_wrong_choice() {
-    entry(&Config {
-        voter_count: 1,
-        candidate_count: 5,
-        test_scheme: TestScheme::WrongVoteCandidate,
-    });
+fn test_consume_guarded_vote_with_early_since() {
+    consume_guarded_vote(GuardProof::SinceTooEarly);
 }
 
 #[test]
-fn test_wrong_tx_type() {
-    entry(&Config {
-        voter_count: 1,
-        candidate_count: 5,
-        test_scheme: TestScheme::WrongTxType,
-    });
+fn test_consume_guarded_vote_with_header_dep() {
+    consume_guarded_vote(GuardProof::HeaderDep);
 }
 
 #[test]
-fn test_no_meta_cell() {
-    entry(&Config {
-        voter_count: 1,
-        candidate_count: 5,
-        test_scheme: TestScheme::NoMetaCell,
-    });
+fn test_consume_guarded_vote_with_early_header_dep() {
+    consume_guarded_vote(GuardProof::HeaderDepTooEarly);
 }
 
 #[test]
-fn test_wrong_args() {
-    entry(&Config {
-        voter_count: 1,
-        candidate_count: 5,
-        test_scheme: TestScheme::WrongArgs,
-    });
+fn test_consume_guarded_vote_with_meta_cell_consumed() {
+    consume_guarded_vote(GuardProof::MetaCellConsumed);
 }
 
 #[test]
-fn test_no_lock_found() {
-    entry(&Config {
-        voter_count: 1,
-        candidate_count: 5,
-        test_scheme: TestScheme::NoLockFound,
-    });
+fn test_consume_guarded_vote_with_result_cell_dep() {
+    consume_guarded_vote(GuardProof::ResultCellDep);
 }
 
-#[test]
-fn test_verify_smt_fail() {
-    entry(&Config {
-        voter_count: 1,
-        candidate_count: 5,
-        test_scheme: TestScheme::VerifySmtFail,
-    });
+pub(crate) enum TallyScheme {
+    Create,
+    CreateNonZero,
+    CreateNotPinned,
+    UpdateWithoutVotes,
 }
 
-#[test]
-fn test_verify_smt_fail_not_on() {
-    entry(&Config {
-        voter_count: 1,
-        candidate_count: 5,
-        test_scheme: TestScheme::VerifySmtNotOn,
-    });
-}
+pub(crate) fn tally_cell(scheme: TallyScheme) {
+    let mut context = Context::default();
+    let out_point = context.deploy_cell_by_name("ckb-dao-vote");
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let always_success_script = context
+        .build_script(&always_success_out_point, Bytes::new())
+        .unwrap();
 
-#[test]
-fn test_molecule_failed() {
-    entry(&Config {
-        voter_count: 1,
-        candidate_count: 5,
-        test_scheme: TestScheme::Molecule,
-    });
-}
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(always_success_script.clone())
+            .build(),
+        Bytes::new(),
+    );
+    let input = CellInput::new_builder()
+        .previous_output(input_out_point)
+        .build();
+    // type id: ckbhash(first input | output index)
+    let mut type_id = [0u8; 32];
+    let mut blake2b = new_blake2b();
+    blake2b.update(input.as_slice());
+    blake2b.update(&0u64.to_le_bytes());
+    blake2b.finalize(&mut type_id);
+    let tally_script = context
+        .build_script(&out_point, Bytes::from(type_id.to_vec()))
+        .unwrap();
 
-#[test]
-fn test_multiple_candidates() {
-    entry(&Config {
-        voter_count: 1,
-        candidate_count: 5,
-        test_scheme: TestScheme::MultipleCandidates,
-    });
+    let vote_meta = VoteMeta {
+        smt_root_hash: None,
+        candidates: vec![vec![0], vec![1]],
+        start_time: 0,
+        end_time: 0,
+        extra: None,
+        tally_type_hash: match scheme {
+            TallyScheme::CreateNotPinned => None,
+            _ => Some(
+                tally_script
+                    .calc_script_hash()
+                    .as_slice()
+                    .try_into()
+                    .unwrap(),
+            ),
+        },
+        ..Default::default()
+    };
+    let vote_meta_out_point = context.deploy_cell(to_vec(&vote_meta, false).unwrap().into());
+    let tally = Tally {
+        session_id: blake160(vote_meta_out_point.as_slice()),
+        counts: match scheme {
+            TallyScheme::CreateNonZero => vec![1, 0],
+            _ => vec![0, 0],
+        },
+        turnout: 0,
+        abstain: 0,
+        voted_root: [0; 32],
+    };
+    let tally_bin = Bytes::from(to_vec(&tally, false).unwrap());
+    let tally_output = CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .lock(always_success_script.clone())
+        .type_(Some(tally_script.clone()).pack())
+        .build();
+
+    // update an existing tally cell instead of creating one
+    let input = if let TallyScheme::UpdateWithoutVotes = scheme {
+        let tally_out_point = context.create_cell(tally_output.clone(), tally_bin.clone());
+        CellInput::new_builder()
+            .previous_output(tally_out_point)
+            .build()
+    } else {
+        input
+    };
+    let tx = TransactionBuilder::default()
+        .cell_dep(
+            CellDep::new_builder()
+                .out_point(vote_meta_out_point)
+                .dep_type(0u8.into())
+                .build(),
+        )
+        .input(input)
+        .output(tally_output)
+        .output_data(tally_bin.pack())
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, 10_000_000);
+    match scheme {
+        TallyScheme::Create => {
+            println!("consume cycles: {}", result.expect("pass verification"));
+        }
+        _ => {
+            assert!(result.is_err());
+        }
+    }
+}
+
+#[test]
+fn test_create_tally() {
+    tally_cell(TallyScheme::Create);
+}
+
+#[test]
+fn test_create_tally_non_zero() {
+    tally_cell(TallyScheme::CreateNonZero);
+}
+
+#[test]
+fn test_create_tally_not_pinned() {
+    tally_cell(TallyScheme::CreateNotPinned);
+}
+
+#[test]
+fn test_update_tally_without_votes() {
+    tally_cell(TallyScheme::UpdateWithoutVotes);
+}
+
+pub(crate) enum FinalizeScheme {
+    Finalize,
+    Passed,
+    QuorumNotReached,
+    ThresholdNotMet,
+    WrongPassed,
+    WrongWinners,
+    MetaCellNotConsumed,
+    UnpinnedTally,
+    ConsumeResult,
+}
+
+pub(crate) fn finalize(scheme: FinalizeScheme) {
+    let mut context = Context::default();
+    let out_point = context.deploy_cell_by_name("ckb-dao-vote");
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let always_success_script = context
+        .build_script(&always_success_out_point, Bytes::new())
+        .unwrap();
+
+    let type_id = [42u8; 32];
+    let tally_script = context
+        .build_script(&out_point, Bytes::from(type_id.to_vec()))
+        .unwrap();
+    let result_script = context
+        .build_script(&out_point, Bytes::from([&type_id[..], &[0]].concat()))
+        .unwrap();
+
+    let vote_meta = VoteMeta {
+        smt_root_hash: None,
+        candidates: vec![vec![0], vec![1], vec![2]],
+        start_time: 0,
+        end_time: 0,
+        extra: None,
+        // another tally can't finalize the session
+        tally_type_hash: Some(match scheme {
+            FinalizeScheme::UnpinnedTally => [43u8; 32],
+            _ => tally_script
+                .calc_script_hash()
+                .as_slice()
+                .try_into()
+                .unwrap(),
+        }),
+        // 40% of 20 must vote, the winner needs more than 60% of the turnout
+        eligible_weight: Some(match scheme {
+            FinalizeScheme::QuorumNotReached => 30,
+            _ => 20,
+        }),
+        quorum: Some(8),
+        quorum_bps: Some(4000),
+        pass_threshold_bps: Some(6000),
+        ..Default::default()
+    };
+    let vote_meta_bin = Bytes::from(to_vec(&vote_meta, false).unwrap());
+    // the vote meta cell is consumed by finalization, give it a lock
+    let vote_meta_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(always_success_script.clone())
+            .build(),
+        vote_meta_bin.clone(),
+    );
+    let session_id = blake160(vote_meta_out_point.as_slice());
+    let tally = Tally {
+        session_id,
+        counts: match scheme {
+            FinalizeScheme::Finalize | FinalizeScheme::WrongWinners => vec![3, 5, 5],
+            FinalizeScheme::ThresholdNotMet => vec![4, 5, 0],
+            _ => vec![3, 6, 0],
+        },
+        turnout: 9,
+        abstain: 0,
+        voted_root: [0; 32],
+    };
+    let tally_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(always_success_script.clone())
+            .type_(Some(tally_script.clone()).pack())
+            .build(),
+        to_vec(&tally, false).unwrap().into(),
+    );
+    let vote_result = VoteResult {
+        session_id,
+        meta_hash: blake2b_256(&vote_meta_bin),
+        winners: match scheme {
+            FinalizeScheme::Finalize => 0b110,
+            _ => 0b010,
+        },
+        counts: tally.counts.clone(),
+        turnout: tally.turnout,
+        abstain: tally.abstain,
+        quorum_reached: match scheme {
+            FinalizeScheme::QuorumNotReached => 0,
+            _ => 1,
+        },
+        passed: match scheme {
+            FinalizeScheme::Passed => 1,
+            _ => 0,
+        },
+    };
+    let result_output = CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .lock(always_success_script.clone())
+        .type_(Some(result_script.clone()).pack())
+        .build();
+    let result_bin = Bytes::from(to_vec(&vote_result, false).unwrap());
+
+    let tx = match scheme {
+        FinalizeScheme::ConsumeResult => {
+            let result_out_point = context.create_cell(result_output.clone(), result_bin);
+            TransactionBuilder::default()
+                .input(
+                    CellInput::new_builder()
+                        .previous_output(result_out_point)
+                        .build(),
+                )
+                .output(
+                    CellOutput::new_builder()
+                        .capacity(1000u64.pack())
+                        .lock(always_success_script.clone())
+                        .build(),
+                )
+                .output_data(Bytes::new().pack())
+                .build()
+        }
+        _ => {
+            let mut tx = TransactionBuilder::default()
+                .input(
+                    CellInput::new_builder()
+                        .previous_output(tally_out_point)
+                        .build(),
+                )
+                .output(result_output)
+                .output_data(result_bin.pack());
+            if let FinalizeScheme::MetaCellNotConsumed = scheme {
+                tx = tx.cell_dep(
+                    CellDep::new_builder()
+                        .out_point(vote_meta_out_point)
+                        .dep_type(0u8.into())
+                        .build(),
+                );
+            } else {
+                tx = tx.input(
+                    CellInput::new_builder()
+                        .previous_output(vote_meta_out_point)
+                        .build(),
+                );
+            }
+            tx.build()
+        }
+    };
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, 10_000_000);
+    match scheme {
+        FinalizeScheme::Finalize
+        | FinalizeScheme::Passed
+        | FinalizeScheme::QuorumNotReached
+        | FinalizeScheme::ThresholdNotMet => {
+            println!("consume cycles: {}", result.expect("pass verification"));
+        }
+        _ => {
+            assert!(result.is_err());
+        }
+    }
+}
+
+#[test]
+fn test_finalize() {
+    finalize(FinalizeScheme::Finalize);
+}
+
+#[test]
+fn test_finalize_passed() {
+    finalize(FinalizeScheme::Passed);
+}
+
+#[test]
+fn test_finalize_quorum_not_reached() {
+    finalize(FinalizeScheme::QuorumNotReached);
+}
+
+#[test]
+fn test_finalize_threshold_not_met() {
+    finalize(FinalizeScheme::ThresholdNotMet);
+}
+
+#[test]
+fn test_finalize_wrong_passed() {
+    finalize(FinalizeScheme::WrongPassed);
+}
+
+#[test]
+fn test_finalize_wrong_winners() {
+    finalize(FinalizeScheme::WrongWinners);
+}
+
+#[test]
+fn test_finalize_meta_cell_not_consumed() {
+    finalize(FinalizeScheme::MetaCellNotConsumed);
+}
+
+#[test]
+fn test_finalize_unpinned_tally() {
+    finalize(FinalizeScheme::UnpinnedTally);
+}
+
+#[test]
+fn test_consume_result() {
+    finalize(FinalizeScheme::ConsumeResult);
+}
+
+pub(crate) enum GovernedLockScheme {
+    Unlock,
+    NoOwnerLock,
+    NoResultCell,
+    CandidateNotWinning,
+    Tie,
+    QuorumNotReached,
+    NotPassed,
+}
+
+pub(crate) fn governed_lock(scheme: GovernedLockScheme) {
+    let mut context = Context::default();
+    let vote_out_point = context.deploy_cell_by_name("ckb-dao-vote");
+    let lock_bin: Bytes = Loader::default().load_binary("ckb-dao-vote-lock");
+    let lock_out_point = context.deploy_cell(lock_bin);
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let always_success_script = context
+        .build_script(&always_success_out_point, Bytes::new())
+        .unwrap();
+    let owner_lock_script = context
+        .build_script(&always_success_out_point, Bytes::from(vec![7]))
+        .unwrap();
+
+    let result_script = context
+        .build_script(
+            &vote_out_point,
+            Bytes::from([&[42u8; 32][..], &[0]].concat()),
+        )
+        .unwrap();
+    let vote_result = VoteResult {
+        session_id: [0u8; 20],
+        meta_hash: [0u8; 32],
+        winners: match scheme {
+            GovernedLockScheme::CandidateNotWinning => 0b001,
+            GovernedLockScheme::Tie => 0b011,
+            _ => 0b010,
+        },
+        counts: vec![3, 5, 1],
+        turnout: 9,
+        abstain: 0,
+        quorum_reached: 1,
+        // the candidate wins, but misses the session's pass threshold
+        passed: match scheme {
+            GovernedLockScheme::NotPassed => 0,
+            _ => 1,
+        },
+    };
+    let result_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(always_success_script.clone())
+            .type_(Some(result_script.clone()).pack())
+            .build(),
+        to_vec(&vote_result, false).unwrap().into(),
+    );
+
+    // candidate 1 must win with a turnout of at least 9
+    let quorum: u64 = match scheme {
+        GovernedLockScheme::QuorumNotReached => 10,
+        _ => 9,
+    };
+    let mut args = result_script.calc_script_hash().as_slice().to_vec();
+    args.push(1);
+    args.extend_from_slice(&quorum.to_le_bytes());
+    args.extend_from_slice(owner_lock_script.calc_script_hash().as_slice());
+    let governed_lock_script = context
+        .build_script(&lock_out_point, Bytes::from(args))
+        .unwrap();
+
+    let treasury_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(governed_lock_script)
+            .build(),
+        Bytes::new(),
+    );
+    let owner_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(owner_lock_script)
+            .build(),
+        Bytes::new(),
+    );
+    let mut tx = TransactionBuilder::default()
+        .input(
+            CellInput::new_builder()
+                .previous_output(treasury_out_point)
+                .build(),
+        )
+        .output(
+            CellOutput::new_builder()
+                .capacity(10000u64.pack())
+                .lock(always_success_script.clone())
+                .build(),
+        )
+        .output_data(Bytes::new().pack());
+    if !matches!(scheme, GovernedLockScheme::NoOwnerLock) {
+        tx = tx.input(
+            CellInput::new_builder()
+                .previous_output(owner_out_point)
+                .build(),
+        );
+    }
+    if !matches!(scheme, GovernedLockScheme::NoResultCell) {
+        tx = tx.cell_dep(
+            CellDep::new_builder()
+                .out_point(result_out_point)
+                .dep_type(0u8.into())
+                .build(),
+        );
+    }
+    let tx = context.complete_tx(tx.build());
+
+    let result = context.verify_tx(&tx, 10_000_000);
+    match scheme {
+        GovernedLockScheme::Unlock => {
+            println!("consume cycles: {}", result.expect("pass verification"));
+        }
+        GovernedLockScheme::NotPassed => {
+            // ProposalNotPassed of the lock script
+            assert_eq!(script_error_code(&result.unwrap_err()), Some(57));
+        }
+        _ => {
+            assert!(result.is_err());
+        }
+    }
+}
+
+#[test]
+fn test_governed_lock_unlock() {
+    governed_lock(GovernedLockScheme::Unlock);
+}
+
+#[test]
+fn test_governed_lock_no_owner_lock() {
+    governed_lock(GovernedLockScheme::NoOwnerLock);
+}
+
+#[test]
+fn test_governed_lock_no_result_cell() {
+    governed_lock(GovernedLockScheme::NoResultCell);
+}
+
+#[test]
+fn test_governed_lock_candidate_not_winning() {
+    governed_lock(GovernedLockScheme::CandidateNotWinning);
+}
+
+#[test]
+fn test_governed_lock_tie() {
+    governed_lock(GovernedLockScheme::Tie);
+}
+
+#[test]
+fn test_governed_lock_quorum_not_reached() {
+    governed_lock(GovernedLockScheme::QuorumNotReached);
+}
+
+#[test]
+fn test_governed_lock_not_passed() {
+    governed_lock(GovernedLockScheme::NotPassed);
+}
+
+pub(crate) enum MetaScheme {
+    Open,
+    OpenBondTooLow,
+    OpenUnsupportedVersion,
+    Close,
+    CloseEarly,
+    CloseUnbonded,
+    Forfeit,
+    ForfeitMissing,
+    OpenInvalidPolicy,
+    CloseAfterEnd,
+    CloseAfterEndEarly,
+    CloseByCommittee,
+    CloseByCommitteeMissing,
+    OpenGuardedRelativeEnd,
+}
+
+const PROPOSER_BOND: u64 = 10_000;
+
+pub(crate) fn meta_cell(scheme: MetaScheme) {
+    let mut context = Context::default();
+    let out_point = context.deploy_cell_by_name("ckb-dao-vote");
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let always_success_script = context
+        .build_script(&always_success_out_point, Bytes::new())
+        .unwrap();
+    let forfeit_lock_script = context
+        .build_script(&always_success_out_point, Bytes::from(vec![0xff]))
+        .unwrap();
+    let meta_script = context
+        .build_script(&out_point, Bytes::from(META_ARGS.to_vec()))
+        .unwrap();
+    // a 2-of-3 committee
+    let committee: Vec<Script> = (0..3u8)
+        .map(|i| {
+            context
+                .build_script(&always_success_out_point, Bytes::from(vec![0xa0 + i]))
+                .unwrap()
+        })
+        .collect();
+    let committee_hashes: Vec<[u8; 32]> = committee
+        .iter()
+        .map(|lock| lock.calc_script_hash().as_slice().try_into().unwrap())
+        .collect();
+
+    // end_time is an absolute block number since
+    let vote_meta = VoteMeta {
+        candidates: vec![b"yes".to_vec(), b"no".to_vec()],
+        end_time: match scheme {
+            MetaScheme::OpenGuardedRelativeEnd => RELATIVE_END,
+            _ => GUARDED_END_BLOCK,
+        },
+        guarded_consumption: match scheme {
+            MetaScheme::OpenGuardedRelativeEnd => Some(1),
+            _ => None,
+        },
+        version: match scheme {
+            MetaScheme::OpenUnsupportedVersion => Some(2),
+            _ => None,
+        },
+        proposer_bond: match scheme {
+            MetaScheme::CloseUnbonded
+            | MetaScheme::OpenInvalidPolicy
+            | MetaScheme::CloseAfterEnd
+            | MetaScheme::CloseAfterEndEarly
+            | MetaScheme::CloseByCommittee
+            | MetaScheme::CloseByCommitteeMissing => None,
+            _ => Some(PROPOSER_BOND),
+        },
+        bond_forfeit_lock_hash: match scheme {
+            MetaScheme::Forfeit | MetaScheme::ForfeitMissing => Some(
+                forfeit_lock_script
+                    .calc_script_hash()
+                    .as_slice()
+                    .try_into()
+                    .unwrap(),
+            ),
+            _ => None,
+        },
+        closure_policy: match scheme {
+            MetaScheme::CloseAfterEnd | MetaScheme::CloseAfterEndEarly => Some(1),
+            MetaScheme::OpenInvalidPolicy
+            | MetaScheme::CloseByCommittee
+            | MetaScheme::CloseByCommitteeMissing => Some(2),
+            _ => None,
+        },
+        committee: match scheme {
+            MetaScheme::CloseByCommittee | MetaScheme::CloseByCommitteeMissing => {
+                Some(committee_hashes)
+            }
+            _ => None,
+        },
+        committee_threshold: match scheme {
+            MetaScheme::CloseByCommittee | MetaScheme::CloseByCommitteeMissing => Some(2),
+            _ => None,
+        },
+        ..Default::default()
+    };
+    let meta_capacity = match scheme {
+        MetaScheme::OpenBondTooLow => PROPOSER_BOND - 1,
+        _ => PROPOSER_BOND,
+    };
+    let meta_output = CellOutput::new_builder()
+        .capacity(meta_capacity.pack())
+        .lock(always_success_script.clone())
+        .type_(Some(meta_script).pack())
+        .build();
+    let meta_bin = Bytes::from(to_vec(&vote_meta, false).unwrap());
+
+    let tx = match scheme {
+        MetaScheme::Open
+        | MetaScheme::OpenBondTooLow
+        | MetaScheme::OpenUnsupportedVersion
+        | MetaScheme::OpenInvalidPolicy
+        | MetaScheme::OpenGuardedRelativeEnd => {
+            let input_out_point = context.create_cell(
+                CellOutput::new_builder()
+                    .capacity((2 * PROPOSER_BOND).pack())
+                    .lock(always_success_script.clone())
+                    .build(),
+                Bytes::new(),
+            );
+            TransactionBuilder::default()
+                .input(
+                    CellInput::new_builder()
+                        .previous_output(input_out_point)
+                        .build(),
+                )
+                .output(meta_output)
+                .output_data(meta_bin.pack())
+                .build()
+        }
+        _ => {
+            let meta_out_point = context.create_cell(meta_output, meta_bin);
+            let refund_lock = match scheme {
+                MetaScheme::Forfeit => forfeit_lock_script,
+                _ => always_success_script.clone(),
+            };
+            let mut tx = TransactionBuilder::default()
+                .input(
+                    CellInput::new_builder()
+                        .previous_output(meta_out_point)
+                        .build(),
+                )
+                .output(
+                    CellOutput::new_builder()
+                        .capacity(PROPOSER_BOND.pack())
+                        .lock(refund_lock)
+                        .build(),
+                )
+                .output_data(Bytes::new().pack());
+            let signers = match scheme {
+                MetaScheme::CloseByCommittee => 2,
+                MetaScheme::CloseByCommitteeMissing => 1,
+                _ => 0,
+            };
+            for lock in committee.into_iter().take(signers) {
+                let input_out_point = context.create_cell(
+                    CellOutput::new_builder()
+                        .capacity(1000u64.pack())
+                        .lock(lock)
+                        .build(),
+                    Bytes::new(),
+                );
+                tx = tx.input(
+                    CellInput::new_builder()
+                        .previous_output(input_out_point)
+                        .build(),
+                );
+            }
+            if !matches!(
+                scheme,
+                MetaScheme::CloseEarly
+                    | MetaScheme::CloseUnbonded
+                    | MetaScheme::CloseAfterEndEarly
+                    | MetaScheme::CloseByCommittee
+                    | MetaScheme::CloseByCommitteeMissing
+            ) {
+                let header = HeaderBuilder::default()
+                    .number(GUARDED_END_BLOCK.pack())
+                    .build();
+                context.insert_header(header.clone());
+                tx = tx.header_dep(header.hash());
+            }
+            tx.build()
+        }
+    };
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, 10_000_000);
+    match scheme {
+        MetaScheme::Open
+        | MetaScheme::Close
+        | MetaScheme::CloseUnbonded
+        | MetaScheme::Forfeit
+        | MetaScheme::CloseAfterEnd
+        | MetaScheme::CloseByCommittee => {
+            println!("consume cycles: {}", result.expect("pass verification"));
+        }
+        MetaScheme::OpenGuardedRelativeEnd => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::WrongArgs.code()));
+        }
+        _ => {
+            assert!(result.is_err());
+        }
+    }
+}
+
+#[test]
+fn test_meta_cell_open() {
+    meta_cell(MetaScheme::Open);
+}
+
+#[test]
+fn test_meta_cell_open_bond_too_low() {
+    meta_cell(MetaScheme::OpenBondTooLow);
+}
+
+#[test]
+fn test_meta_cell_open_unsupported_version() {
+    meta_cell(MetaScheme::OpenUnsupportedVersion);
+}
+
+#[test]
+fn test_meta_cell_open_guarded_relative_end() {
+    meta_cell(MetaScheme::OpenGuardedRelativeEnd);
+}
+
+#[test]
+fn test_meta_cell_close() {
+    meta_cell(MetaScheme::Close);
+}
+
+#[test]
+fn test_meta_cell_close_early() {
+    meta_cell(MetaScheme::CloseEarly);
+}
+
+#[test]
+fn test_meta_cell_close_unbonded() {
+    meta_cell(MetaScheme::CloseUnbonded);
+}
+
+#[test]
+fn test_meta_cell_forfeit() {
+    meta_cell(MetaScheme::Forfeit);
+}
+
+#[test]
+fn test_meta_cell_forfeit_missing() {
+    meta_cell(MetaScheme::ForfeitMissing);
+}
+
+#[test]
+fn test_meta_cell_open_invalid_policy() {
+    meta_cell(MetaScheme::OpenInvalidPolicy);
+}
+
+#[test]
+fn test_meta_cell_close_after_end() {
+    meta_cell(MetaScheme::CloseAfterEnd);
+}
+
+#[test]
+fn test_meta_cell_close_after_end_early() {
+    meta_cell(MetaScheme::CloseAfterEndEarly);
+}
+
+#[test]
+fn test_meta_cell_close_by_committee() {
+    meta_cell(MetaScheme::CloseByCommittee);
+}
+
+#[test]
+fn test_meta_cell_close_by_committee_missing() {
+    meta_cell(MetaScheme::CloseByCommitteeMissing);
+}
+
+pub(crate) enum TestScheme {
+    Normal,
+    Molecule,
+    WrongTxType,
+    WrongArgs,
+    NoMetaCell,
+    VerifySmtFail,
+    VerifySmtNotOn,
+    NoLockFound,
+    WrongVoteCandidate,
+    WrongVoteCandidateExceedLimit,
+    MultipleCandidates,
+    LegacyVoteMeta,
+    VoteCapacityInRange,
+    VoteCapacityTooLow,
+    VoteCapacityTooHigh,
+    VoteCapacityInverted,
+    GuardedVote,
+    GuardedVoteWrongArgs,
+    GuardedVoteRelativeEnd,
+    Tally,
+    TallyWeighted,
+    TallyMismatch,
+    TallyMissing,
+    TallyForgedResult,
+    TallyDoubleVote,
+    Abstain,
+    AbstainNotAllowed,
+    WrongBallotLength,
+    TooManyCandidates,
+    TallyAbstain,
+    CandidateRecords,
+    CandidateRecordsDuplicateId,
+    CandidateRecordsInvalidTitle,
+    CandidateRecordsWrongCount,
+    ExplicitVersion,
+    UnsupportedVersion,
+    ProposerBond,
+    ProposerBondWithoutMetaType,
+    DepositSnapshot,
+    DepositAfterSnapshot,
+    DepositMissing,
+    VersionTwoBallot,
+    EscrowVote,
+    EscrowTooShort,
+    EscrowWrongWeight,
+    EscrowVersionOne,
+    EscrowFractionalEpoch,
+    EscrowWrongHashType,
+    MissingWitness,
+    CorruptWitness,
+}
+
+pub(crate) struct Config {
+    voter_count: usize,
+    candidate_count: usize,
+    test_scheme: TestScheme,
+}
+
+fn candidate_records(config: &Config) -> Option<CandidateVec> {
+    let count = match config.test_scheme {
+        TestScheme::CandidateRecords
+        | TestScheme::CandidateRecordsDuplicateId
+        | TestScheme::CandidateRecordsInvalidTitle => config.candidate_count,
+        TestScheme::CandidateRecordsWrongCount => config.candidate_count - 1,
+        _ => return None,
+    };
+    let records = (0..count)
+        .map(|i| Candidate {
+            id: match config.test_scheme {
+                TestScheme::CandidateRecordsDuplicateId => 7,
+                _ => i as u32,
+            },
+            title: match config.test_scheme {
+                TestScheme::CandidateRecordsInvalidTitle => vec![0xff, 0xfe],
+                _ => format!("proposal {}", i).into_bytes(),
+            },
+            content_hash: blake2b_256(i.to_le_bytes()),
+            recipient_lock_hash: (i == 0).then_some([1u8; 32]),
+        })
+        .collect();
+    Some(CandidateVec(records))
+}
+
+pub(crate) fn entry(config: &Config) {
+    let mut context = Context::default();
+
+    // collect all voter's lock script hashes into SMT
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let mut smt_tree = CkbSMT::default();
+    for i in 0..config.voter_count {
+        // make args different to represent different voters
+        let voter_lock_script = context
+            .build_script(&always_success_out_point, Bytes::from(vec![i as u8]))
+            .unwrap();
+        let script_hash = voter_lock_script.calc_script_hash();
+        let key: [u8; 32] = script_hash.as_slice().try_into().unwrap();
+        smt_tree
+            .update(key.into(), SMT_VALUE.clone().into())
+            .unwrap();
+    }
+    let smt_root_hash = match config.test_scheme {
+        TestScheme::VerifySmtNotOn => [0u8; 32],
+        _ => smt_tree.root().clone().into(),
+    };
+    let out_point = context.deploy_cell_by_name("ckb-dao-vote");
+
+    // the tally cell already exists, its type id is arbitrary
+    let tally_script = context
+        .build_script(&out_point, Bytes::from(vec![42u8; 32]))
+        .unwrap();
+    let tally_enabled = matches!(
+        config.test_scheme,
+        TestScheme::Tally
+            | TestScheme::TallyWeighted
+            | TestScheme::TallyMismatch
+            | TestScheme::TallyMissing
+            | TestScheme::TallyForgedResult
+            | TestScheme::TallyDoubleVote
+            | TestScheme::TallyAbstain
+    );
+
+    // deposits are made in block 900, or 1100 if after the snapshot in block 1000
+    let deposit_enabled = matches!(
+        config.test_scheme,
+        TestScheme::DepositSnapshot | TestScheme::DepositAfterSnapshot | TestScheme::DepositMissing
+    );
+    let snapshot_header = HeaderBuilder::default().number(1000u64.pack()).build();
+    let deposit_header = HeaderBuilder::default()
+        .number(match config.test_scheme {
+            TestScheme::DepositAfterSnapshot => 1100u64.pack(),
+            _ => 900u64.pack(),
+        })
+        .build();
+    let deposit_type_script = context
+        .build_script(&always_success_out_point, Bytes::from(vec![0xda]))
+        .unwrap();
+
+    // Escrow vote cells are locked for 12 epochs, 3 if too short, or 11 and a
+    // half. With a minimum of 6 epochs, a 12-epoch lock doubles the weight.
+    let escrow_enabled = matches!(
+        config.test_scheme,
+        TestScheme::EscrowVote
+            | TestScheme::EscrowTooShort
+            | TestScheme::EscrowWrongWeight
+            | TestScheme::EscrowVersionOne
+            | TestScheme::EscrowFractionalEpoch
+            | TestScheme::EscrowWrongHashType
+    );
+    // (number, index, length) of the relative epoch since
+    let escrow_epochs: (u64, u64, u64) = match config.test_scheme {
+        TestScheme::EscrowTooShort => (3, 0, 1),
+        TestScheme::EscrowFractionalEpoch => (11, 1, 2),
+        _ => (12, 0, 1),
+    };
+    let escrow_since = 0xa000_0000_0000_0000u64
+        | (escrow_epochs.2 << 40)
+        | (escrow_epochs.1 << 24)
+        | escrow_epochs.0;
+    let escrow_lock_script = context
+        .build_script(
+            &always_success_out_point,
+            Bytes::from([&[0u8; 20][..], &escrow_since.to_le_bytes()].concat()),
+        )
+        .unwrap();
+
+    // every vote cell below holds 500 shannons
+    let (min_vote_capacity, max_vote_capacity) = match config.test_scheme {
+        TestScheme::VoteCapacityInRange => (Some(500), Some(500)),
+        TestScheme::VoteCapacityTooLow => (Some(501), None),
+        TestScheme::VoteCapacityTooHigh => (None, Some(499)),
+        TestScheme::VoteCapacityInverted => (Some(501), Some(499)),
+        _ => (None, None),
+    };
+    let vote_meta = VoteMeta {
+        smt_root_hash: Some(smt_root_hash),
+        candidates: (0..config.candidate_count).map(|i| vec![i as u8]).collect(),
+        start_time: 0,
+        end_time: match config.test_scheme {
+            TestScheme::GuardedVoteRelativeEnd => RELATIVE_END,
+            _ => 0,
+        },
+        extra: None,
+        min_vote_capacity,
+        max_vote_capacity,
+        guarded_consumption: match config.test_scheme {
+            TestScheme::GuardedVote
+            | TestScheme::GuardedVoteWrongArgs
+            | TestScheme::GuardedVoteRelativeEnd => Some(1),
+            _ => None,
+        },
+        tally_type_hash: if tally_enabled {
+            Some(
+                tally_script
+                    .calc_script_hash()
+                    .as_slice()
+                    .try_into()
+                    .unwrap(),
+            )
+        } else {
+            None
+        },
+        weight_mode: match config.test_scheme {
+            TestScheme::TallyWeighted => Some(1),
+            _ if escrow_enabled => Some(2),
+            _ => None,
+        },
+        allow_abstain: match config.test_scheme {
+            TestScheme::Abstain | TestScheme::TallyAbstain => Some(1),
+            _ => None,
+        },
+        candidate_records: candidate_records(config),
+        version: match config.test_scheme {
+            TestScheme::ExplicitVersion => Some(1),
+            TestScheme::UnsupportedVersion => Some(3),
+            TestScheme::VersionTwoBallot
+            | TestScheme::EscrowVote
+            | TestScheme::EscrowTooShort
+            | TestScheme::EscrowWrongWeight
+            | TestScheme::EscrowFractionalEpoch
+            | TestScheme::EscrowWrongHashType => Some(2),
+            _ => None,
+        },
+        proposer_bond: match config.test_scheme {
+            TestScheme::ProposerBond | TestScheme::ProposerBondWithoutMetaType => {
+                Some(PROPOSER_BOND)
+            }
+            _ => None,
+        },
+        eligibility_mode: deposit_enabled.then_some(1),
+        snapshot_block_hash: deposit_enabled
+            .then(|| snapshot_header.hash().as_slice().try_into().unwrap()),
+        deposit_type_hash: deposit_enabled.then(|| {
+            deposit_type_script
+                .calc_script_hash()
+                .as_slice()
+                .try_into()
+                .unwrap()
+        }),
+        escrow_lock_code_hash: escrow_enabled.then(|| {
+            escrow_lock_script
+                .code_hash()
+                .as_slice()
+                .try_into()
+                .unwrap()
+        }),
+        escrow_min_epochs: escrow_enabled.then_some(6),
+        escrow_max_epochs: escrow_enabled.then_some(24),
+        escrow_lock_hash_type: escrow_enabled.then(|| match config.test_scheme {
+            TestScheme::EscrowWrongHashType => ScriptHashType::Data.into(),
+            _ => escrow_lock_script.hash_type().into(),
+        }),
+        ..Default::default()
+    };
+    let vote_meta_bin = match config.test_scheme {
+        TestScheme::LegacyVoteMeta => to_vec(
+            &LegacyVoteMeta {
+                smt_root_hash: vote_meta.smt_root_hash,
+                candidates: vote_meta.candidates.clone(),
+                start_time: vote_meta.start_time,
+                end_time: vote_meta.end_time,
+                extra: None,
+            },
+            false,
+        )
+        .unwrap(),
+        _ => to_vec(&vote_meta, false).unwrap(),
+    };
+
+    let vote_meta_bin = match config.test_scheme {
+        TestScheme::Molecule => vote_meta_bin.iter().map(|_| 0).collect(),
+        _ => vote_meta_bin,
+    };
+    let vote_meta_out_point = match config.test_scheme {
+        TestScheme::ProposerBond => {
+            let meta_script = context
+                .build_script(&out_point, Bytes::from(META_ARGS.to_vec()))
+                .unwrap();
+            let lock = context
+                .build_script(&always_success_out_point, Bytes::new())
+                .unwrap();
+            context.create_cell(
+                CellOutput::new_builder()
+                    .capacity(PROPOSER_BOND.pack())
+                    .lock(lock)
+                    .type_(Some(meta_script).pack())
+                    .build(),
+                vote_meta_bin.into(),
+            )
+        }
+        _ => context.deploy_cell(vote_meta_bin.into()),
+    };
+
+    let args = match config.test_scheme {
+        TestScheme::NoMetaCell => [0u8; 20],
+        _ => blake160(vote_meta_out_point.as_slice()),
+    };
+    let args: Vec<u8> = match config.test_scheme {
+        TestScheme::WrongArgs => vec![0u8; 10],
+        TestScheme::GuardedVote | TestScheme::GuardedVoteRelativeEnd => {
+            [&args[..], &vote_meta.end_time.to_le_bytes()].concat()
+        }
+        _ => args.to_vec(),
+    };
+    let type_script = context.build_script(&out_point, Bytes::from(args)).unwrap();
+    let always_success_script = context
+        .build_script(&always_success_out_point, Bytes::new())
+        .unwrap();
+
+    let mut inputs = vec![];
+    let mut outputs = vec![];
+    let mut outputs_data = vec![];
+    let mut witnesses = vec![];
+    let mut voters = vec![];
+    let mut deposit_cell_deps = vec![];
+    for i in 0..config.voter_count {
+        // make args different to represent different voters
+        let voter_lock_script = context
+            .build_script(&always_success_out_point, Bytes::from(vec![i as u8]))
+            .unwrap();
+
+        let input_type_script = match config.test_scheme {
+            TestScheme::WrongTxType => Some(type_script.clone()).pack(),
+            _ => None::<Script>.pack(),
+        };
+
+        let input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(voter_lock_script.clone())
+                .type_(input_type_script)
+                .build(),
+            Bytes::new(),
+        );
+        inputs.push(
+            CellInput::new_builder()
+                .previous_output(input_out_point)
+                .build(),
+        );
+        if deposit_enabled && !matches!(config.test_scheme, TestScheme::DepositMissing) {
+            let deposit_out_point = context.create_cell(
+                CellOutput::new_builder()
+                    .capacity(10_000u64.pack())
+                    .lock(voter_lock_script.clone())
+                    .type_(Some(deposit_type_script.clone()).pack())
+                    .build(),
+                Bytes::from(vec![0u8; 8]),
+            );
+            context.link_cell_with_block(deposit_out_point.clone(), deposit_header.hash(), 0);
+            deposit_cell_deps.push(
+                CellDep::new_builder()
+                    .out_point(deposit_out_point)
+                    .dep_type(0u8.into())
+                    .build(),
+            );
+        }
+        let vote_lock_script = if escrow_enabled {
+            escrow_lock_script.clone()
+        } else {
+            always_success_script.clone()
+        };
+        outputs.push(
+            CellOutput::new_builder()
+                .capacity(500u64.pack())
+                .lock(vote_lock_script)
+                .type_(Some(type_script.clone()).pack())
+                .build(),
+        );
+        // choice
+        match config.test_scheme {
+            TestScheme::WrongVoteCandidate => {
+                outputs_data.push(Bytes::from(vec![0, 0, 0, 1]).pack());
+            }
+            TestScheme::WrongVoteCandidateExceedLimit => {
+                let data: u32 = 1 << config.candidate_count;
+                outputs_data.push(Bytes::copy_from_slice(&data.to_le_bytes()).pack());
+            }
+            TestScheme::MultipleCandidates => {
+                // select all choices
+                let data: u32 = (1 << config.candidate_count) - 1;
+                outputs_data.push(Bytes::copy_from_slice(&data.to_le_bytes()).pack());
+            }
+            TestScheme::Abstain | TestScheme::AbstainNotAllowed | TestScheme::TallyAbstain => {
+                outputs_data.push(Bytes::from(vec![0, 0, 0, 0]).pack());
+            }
+            TestScheme::WrongBallotLength => {
+                outputs_data.push(Bytes::from(vec![1, 0, 0, 0, 0]).pack());
+            }
+            TestScheme::VersionTwoBallot
+            | TestScheme::EscrowVote
+            | TestScheme::EscrowTooShort
+            | TestScheme::EscrowWrongWeight
+            | TestScheme::EscrowFractionalEpoch
+            | TestScheme::EscrowWrongHashType => {
+                // version 2 ballots record the weight
+                let (number, index, length) = escrow_epochs;
+                let weight: u64 = match config.test_scheme {
+                    TestScheme::VersionTwoBallot => 1,
+                    TestScheme::EscrowWrongWeight => 500,
+                    _ => 500 * (number * length + index) / (6 * length),
+                };
+                let data = [&[1, 0, 0, 0][..], &weight.to_le_bytes()].concat();
+                outputs_data.push(Bytes::from(data).pack());
+            }
+            _ => {
+                outputs_data.push(Bytes::from(vec![1, 0, 0, 0]).pack());
+            }
+        }
+
+        // witness
+        let key: [u8; 32] = voter_lock_script
+            .calc_script_hash()
+            .as_slice()
+            .try_into()
+            .unwrap();
+        let proof = smt_tree.merkle_proof(vec![key.into()]).unwrap();
+        let compiled_proof = proof.clone().compile(vec![key.into()]).unwrap();
+
+        let key = match config.test_scheme {
+            TestScheme::NoLockFound => [0u8; 32],
+            _ => key,
+        };
+        let smt_proof = match config.test_scheme {
+            TestScheme::VerifySmtFail => vec![0u8; 1],
+            _ => compiled_proof.0,
+        };
+        voters.push(key);
+        let vote_proof = VoteProof {
+            lock_script_hash: key,
+            smt_proof,
+        };
+
+        let witness_args = WitnessArgs {
+            lock: None,
+            input_type: None,
+            output_type: match config.test_scheme {
+                TestScheme::CorruptWitness => None,
+                _ => Some(to_vec(&vote_proof, false).unwrap()),
+            },
+        };
+        let witness_args = Bytes::from(to_vec(&witness_args, false).unwrap());
+        if !matches!(config.test_scheme, TestScheme::MissingWitness) {
+            witnesses.push(witness_args.pack());
+        }
+    }
+    if tally_enabled && !matches!(config.test_scheme, TestScheme::TallyMissing) {
+        let session_id = blake160(vote_meta_out_point.as_slice());
+        // the voted set proof shows the voters absent before and present after
+        let mut voted_tree = CkbSMT::default();
+        if let TestScheme::TallyDoubleVote = config.test_scheme {
+            voted_tree
+                .update(voters[0].into(), SMT_VALUE.clone().into())
+                .unwrap();
+        }
+        let voter_keys: Vec<H256> = voters.iter().map(|voter| (*voter).into()).collect();
+        let voted_proof = voted_tree
+            .merkle_proof(voter_keys.clone())
+            .unwrap()
+            .compile(voter_keys.clone())
+            .unwrap();
+        let tally_input = Tally {
+            session_id,
+            counts: vec![0; config.candidate_count],
+            turnout: 0,
+            abstain: 0,
+            voted_root: (*voted_tree.root()).into(),
+        };
+        for key in voter_keys {
+            voted_tree.update(key, SMT_VALUE.clone().into()).unwrap();
+        }
+        // every voter votes for candidate 0, or abstains
+        let weight = match config.test_scheme {
+            TestScheme::TallyWeighted => 500,
+            _ => 1,
+        };
+        let mut tally_output = tally_input.clone();
+        match config.test_scheme {
+            TestScheme::TallyAbstain => tally_output.abstain = weight * config.voter_count as u64,
+            _ => tally_output.counts[0] = weight * config.voter_count as u64,
+        }
+        tally_output.turnout = weight * config.voter_count as u64;
+        tally_output.voted_root = (*voted_tree.root()).into();
+        if let TestScheme::TallyMismatch = config.test_scheme {
+            tally_output.counts[0] += 1;
+        }
+        let tally_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(always_success_script.clone())
+                .type_(Some(tally_script.clone()).pack())
+                .build(),
+            to_vec(&tally_input, false).unwrap().into(),
+        );
+        inputs.push(
+            CellInput::new_builder()
+                .previous_output(tally_out_point)
+                .build(),
+        );
+        // the proof goes into the witness at the tally input's index
+        let witness_args = WitnessArgs {
+            lock: None,
+            input_type: Some(voted_proof.0),
+            output_type: None,
+        };
+        witnesses.push(Bytes::from(to_vec(&witness_args, false).unwrap()).pack());
+        outputs.push(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(always_success_script.clone())
+                .type_(Some(tally_script.clone()).pack())
+                .build(),
+        );
+        outputs_data.push(Bytes::from(to_vec(&tally_output, false).unwrap()).pack());
+        // a result cell can't ride along with a tally update
+        if let TestScheme::TallyForgedResult = config.test_scheme {
+            let result_script = context
+                .build_script(&out_point, Bytes::from([&[42u8; 32][..], &[0]].concat()))
+                .unwrap();
+            let vote_result = VoteResult {
+                session_id,
+                meta_hash: [0u8; 32],
+                winners: 0b1,
+                counts: tally_output.counts.clone(),
+                turnout: tally_output.turnout,
+                abstain: tally_output.abstain,
+                quorum_reached: 1,
+                passed: 1,
+            };
+            outputs.push(
+                CellOutput::new_builder()
+                    .capacity(1000u64.pack())
+                    .lock(always_success_script.clone())
+                    .type_(Some(result_script).pack())
+                    .build(),
+            );
+            outputs_data.push(Bytes::from(to_vec(&vote_result, false).unwrap()).pack());
+        }
+    }
+    let vote_meta_cell_dep = CellDep::new_builder()
+        .out_point(vote_meta_out_point)
+        .dep_type(0u8.into())
+        .build();
+    let mut tx = TransactionBuilder::default()
+        .cell_dep(vote_meta_cell_dep)
+        .cell_deps(deposit_cell_deps)
+        .inputs(inputs)
+        .outputs(outputs)
+        .outputs_data(outputs_data)
+        .witnesses(witnesses);
+    if deposit_enabled {
+        context.insert_header(snapshot_header.clone());
+        context.insert_header(deposit_header.clone());
+        tx = tx
+            .header_dep(snapshot_header.hash())
+            .header_dep(deposit_header.hash());
+    }
+    let tx = tx.build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, 10_000_000);
+    match config.test_scheme {
+        TestScheme::Normal
+        | TestScheme::MultipleCandidates
+        | TestScheme::LegacyVoteMeta
+        | TestScheme::VoteCapacityInRange
+        | TestScheme::GuardedVote
+        | TestScheme::Tally
+        | TestScheme::TallyWeighted
+        | TestScheme::Abstain
+        | TestScheme::TallyAbstain
+        | TestScheme::CandidateRecords
+        | TestScheme::ExplicitVersion
+        | TestScheme::ProposerBond
+        | TestScheme::DepositSnapshot
+        | TestScheme::VersionTwoBallot
+        | TestScheme::EscrowVote
+        | TestScheme::EscrowFractionalEpoch => {
+            assert!(result.is_ok());
+            println!("consume cycles: {}", result.unwrap());
+        }
+        TestScheme::WrongVoteCandidate | TestScheme::WrongVoteCandidateExceedLimit => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::WrongVoteCandidate.code()));
+        }
+        TestScheme::AbstainNotAllowed => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::AbstainNotAllowed.code()));
+        }
+        TestScheme::WrongBallotLength => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::WrongBallotLength.code()));
+        }
+        TestScheme::TooManyCandidates => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::TooManyCandidates.code()));
+        }
+        TestScheme::TallyForgedResult => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::WrongResult.code()));
+        }
+        TestScheme::TallyMismatch | TestScheme::TallyMissing | TestScheme::TallyDoubleVote => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::WrongTally.code()));
+        }
+        TestScheme::GuardedVoteWrongArgs | TestScheme::GuardedVoteRelativeEnd => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::WrongArgs.code()));
+        }
+        TestScheme::VoteCapacityTooLow
+        | TestScheme::VoteCapacityTooHigh
+        | TestScheme::VoteCapacityInverted => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::WrongVoteCapacity.code()));
+        }
+        TestScheme::EscrowTooShort
+        | TestScheme::EscrowWrongWeight
+        | TestScheme::EscrowWrongHashType => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::WrongBallotWeight.code()));
+        }
+        TestScheme::EscrowVersionOne => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::WrongWeightMode.code()));
+        }
+        TestScheme::CandidateRecordsDuplicateId
+        | TestScheme::CandidateRecordsInvalidTitle
+        | TestScheme::CandidateRecordsWrongCount => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::WrongCandidateRecord.code()));
+        }
+        TestScheme::UnsupportedVersion => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::UnsupportedVersion.code()));
+        }
+        TestScheme::ProposerBondWithoutMetaType => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::WrongBond.code()));
+        }
+        TestScheme::DepositAfterSnapshot | TestScheme::DepositMissing => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::WrongEligibility.code()));
+        }
+        TestScheme::Molecule => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::CorruptMeta.code()));
+        }
+        TestScheme::MissingWitness => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::MissingWitness.code()));
+        }
+        TestScheme::CorruptWitness => {
+            let code = script_error_code(&result.unwrap_err());
+            assert_eq!(code, Some(ErrorCode::CorruptWitness.code()));
+        }
+        _ => {
+            assert!(result.is_err());
+        }
+    }
+}
+
+#[test]
+fn test_one_vote() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 1,
+        test_scheme: TestScheme::Normal,
+    });
+}
+
+#[test]
+fn test_multiple_vote() {
+    entry(&Config {
+        voter_count: 3,
+        candidate_count: 5,
+        test_scheme: TestScheme::Normal,
+    });
+}
+
+#[test]
+fn test_wrong_choice() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::WrongVoteCandidate,
+    });
+}
+
+#[test]
+fn test_wrong_tx_type() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::WrongTxType,
+    });
+}
+
+#[test]
+fn test_no_meta_cell() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::NoMetaCell,
+    });
+}
+
+#[test]
+fn test_wrong_args() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::WrongArgs,
+    });
+}
+
+#[test]
+fn test_no_lock_found() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::NoLockFound,
+    });
+}
+
+#[test]
+fn test_verify_smt_fail() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::VerifySmtFail,
+    });
+}
+
+#[test]
+fn test_verify_smt_fail_not_on() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::VerifySmtNotOn,
+    });
+}
+
+#[test]
+fn test_molecule_failed() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::Molecule,
+    });
+}
+
+#[test]
+fn test_multiple_candidates() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::MultipleCandidates,
+    });
+}
+
+#[test]
+fn test_wrong_vote_candidate_exceed_limit() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::WrongVoteCandidateExceedLimit,
+    });
+}
+
+#[test]
+fn test_legacy_vote_meta() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::LegacyVoteMeta,
+    });
+}
+
+#[test]
+fn test_vote_capacity_in_range() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::VoteCapacityInRange,
+    });
+}
+
+#[test]
+fn test_vote_capacity_too_low() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::VoteCapacityTooLow,
+    });
+}
+
+#[test]
+fn test_vote_capacity_too_high() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::VoteCapacityTooHigh,
+    });
+}
+
+#[test]
+fn test_vote_capacity_inverted() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::VoteCapacityInverted,
+    });
+}
+
+#[test]
+fn test_guarded_vote() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::GuardedVote,
+    });
+}
+
+#[test]
+fn test_guarded_vote_wrong_args() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::GuardedVoteWrongArgs,
+    });
+}
+
+#[test]
+fn test_guarded_vote_relative_end() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::GuardedVoteRelativeEnd,
+    });
+}
+
+#[test]
+fn test_tally() {
+    entry(&Config {
+        voter_count: 3,
+        candidate_count: 5,
+        test_scheme: TestScheme::Tally,
+    });
+}
+
+#[test]
+fn test_tally_weighted() {
+    entry(&Config {
+        voter_count: 3,
+        candidate_count: 5,
+        test_scheme: TestScheme::TallyWeighted,
+    });
+}
+
+#[test]
+fn test_tally_mismatch() {
+    entry(&Config {
+        voter_count: 3,
+        candidate_count: 5,
+        test_scheme: TestScheme::TallyMismatch,
+    });
+}
+
+#[test]
+fn test_tally_missing() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::TallyMissing,
+    });
+}
+
+#[test]
+fn test_tally_forged_result() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::TallyForgedResult,
+    });
+}
+
+#[test]
+fn test_tally_double_vote() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::TallyDoubleVote,
+    });
+}
+
+#[test]
+fn test_abstain() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::Abstain,
+    });
+}
+
+#[test]
+fn test_abstain_not_allowed() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::AbstainNotAllowed,
+    });
+}
+
+#[test]
+fn test_wrong_ballot_length() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::WrongBallotLength,
+    });
+}
+
+#[test]
+fn test_too_many_candidates() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 33,
+        test_scheme: TestScheme::TooManyCandidates,
+    });
+}
+
+#[test]
+fn test_tally_abstain() {
+    entry(&Config {
+        voter_count: 3,
+        candidate_count: 5,
+        test_scheme: TestScheme::TallyAbstain,
+    });
+}
+
+#[test]
+fn test_candidate_records() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::CandidateRecords,
+    });
+}
+
+#[test]
+fn test_candidate_records_duplicate_id() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::CandidateRecordsDuplicateId,
+    });
+}
+
+#[test]
+fn test_candidate_records_invalid_title() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::CandidateRecordsInvalidTitle,
+    });
+}
+
+#[test]
+fn test_candidate_records_wrong_count() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::CandidateRecordsWrongCount,
+    });
+}
+
+#[test]
+fn test_explicit_version() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::ExplicitVersion,
+    });
+}
+
+#[test]
+fn test_unsupported_version() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::UnsupportedVersion,
+    });
+}
+
+#[test]
+fn test_proposer_bond() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::ProposerBond,
+    });
+}
+
+#[test]
+fn test_proposer_bond_without_meta_type() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::ProposerBondWithoutMetaType,
+    });
+}
+
+#[test]
+fn test_deposit_snapshot() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::DepositSnapshot,
+    });
+}
+
+#[test]
+fn test_deposit_after_snapshot() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::DepositAfterSnapshot,
+    });
+}
+
+#[test]
+fn test_deposit_missing() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::DepositMissing,
+    });
+}
+
+#[test]
+fn test_version_two_ballot() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::VersionTwoBallot,
+    });
+}
+
+#[test]
+fn test_escrow_vote() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::EscrowVote,
+    });
+}
+
+#[test]
+fn test_escrow_too_short() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::EscrowTooShort,
+    });
+}
+
+#[test]
+fn test_escrow_wrong_weight() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::EscrowWrongWeight,
+    });
 }
 
 #[test]
-fn test_wrong_vote_candidate_exceed_limit() {
+fn test_escrow_version_one() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::EscrowVersionOne,
+    });
+}
+
+#[test]
+fn test_escrow_fractional_epoch() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::EscrowFractionalEpoch,
+    });
+}
+
+#[test]
+fn test_escrow_wrong_hash_type() {
+    entry(&Config {
+        voter_count: 2,
+        candidate_count: 5,
+        test_scheme: TestScheme::EscrowWrongHashType,
+    });
+}
+
+// The serde models are written by hand, the readers are generated from
+// `vote.mol`. Every field is written through the model and read back through
+// the generated reader, so a field that is missing, reordered or of another
+// type in either representation fails here.
+#[test]
+fn test_models_match_schema() {
+    use ckb_dao_vote_types::molecules::{vote as schema, VOTE_META_FIELD_COUNT};
+    use molecule::lazy_reader::Cursor;
+
+    fn raw(cursor: Cursor) -> Vec<u8> {
+        cursor.try_into().unwrap()
+    }
+
+    let candidates = vec![
+        Candidate {
+            id: 15,
+            title: b"title".to_vec(),
+            content_hash: [15; 32],
+            recipient_lock_hash: None,
+        },
+        Candidate {
+            id: 16,
+            title: b"other".to_vec(),
+            content_hash: [16; 32],
+            recipient_lock_hash: Some([17; 32]),
+        },
+    ];
+    let vote_meta = VoteMeta {
+        smt_root_hash: Some([1; 32]),
+        candidates: vec![vec![0], vec![1, 2]],
+        start_time: 2,
+        end_time: 3,
+        extra: Some(vec![4]),
+        min_vote_capacity: Some(5),
+        max_vote_capacity: Some(6),
+        guarded_consumption: Some(7),
+        tally_type_hash: Some([8; 32]),
+        weight_mode: Some(9),
+        eligible_weight: Some(10),
+        quorum: Some(11),
+        quorum_bps: Some(12),
+        pass_threshold_bps: Some(13),
+        allow_abstain: Some(14),
+        candidate_records: Some(CandidateVec(candidates.clone())),
+        version: Some(16),
+        proposer_bond: Some(17),
+        bond_forfeit_lock_hash: Some([18; 32]),
+        closure_policy: Some(19),
+        committee: Some(vec![[20; 32], [21; 32]]),
+        committee_threshold: Some(21),
+        eligibility_mode: Some(22),
+        snapshot_block_hash: Some([23; 32]),
+        deposit_type_hash: Some([24; 32]),
+        escrow_lock_code_hash: Some([25; 32]),
+        escrow_min_epochs: Some(26),
+        escrow_max_epochs: Some(27),
+        escrow_lock_hash_type: Some(28),
+    };
+    let reader = schema::VoteMeta::from(Cursor::from(to_vec(&vote_meta, false).unwrap()));
+    reader.verify(true).unwrap();
+    reader.verify_compatible().unwrap();
+    assert_eq!(
+        reader.cursor.table_actual_field_count().unwrap(),
+        VOTE_META_FIELD_COUNT
+    );
+    assert_eq!(reader.smt_root_hash().unwrap(), vote_meta.smt_root_hash);
+    let read_candidates: Vec<Vec<u8>> = reader.candidates().unwrap().into_iter().map(raw).collect();
+    assert_eq!(read_candidates, vote_meta.candidates);
+    assert_eq!(reader.start_time().unwrap(), vote_meta.start_time);
+    assert_eq!(reader.end_time().unwrap(), vote_meta.end_time);
+    assert_eq!(reader.extra().unwrap().map(raw), vote_meta.extra);
+    assert_eq!(
+        reader.min_vote_capacity_opt().unwrap(),
+        vote_meta.min_vote_capacity
+    );
+    assert_eq!(
+        reader.max_vote_capacity_opt().unwrap(),
+        vote_meta.max_vote_capacity
+    );
+    assert_eq!(
+        reader.guarded_consumption_opt().unwrap(),
+        vote_meta.guarded_consumption
+    );
+    assert_eq!(
+        reader.tally_type_hash_opt().unwrap(),
+        vote_meta.tally_type_hash
+    );
+    assert_eq!(reader.weight_mode_opt().unwrap(), vote_meta.weight_mode);
+    assert_eq!(
+        reader.eligible_weight_opt().unwrap(),
+        vote_meta.eligible_weight
+    );
+    assert_eq!(reader.quorum_opt().unwrap(), vote_meta.quorum);
+    assert_eq!(reader.quorum_bps_opt().unwrap(), vote_meta.quorum_bps);
+    assert_eq!(
+        reader.pass_threshold_bps_opt().unwrap(),
+        vote_meta.pass_threshold_bps
+    );
+    assert_eq!(reader.allow_abstain_opt().unwrap(), vote_meta.allow_abstain);
+    let records = reader.candidate_records_opt().unwrap().unwrap();
+    assert_eq!(records.len().unwrap(), candidates.len());
+    for (record, candidate) in records.iter().zip(&candidates) {
+        assert_eq!(record.id().unwrap(), candidate.id);
+        assert_eq!(raw(record.title().unwrap()), candidate.title);
+        assert_eq!(record.content_hash().unwrap(), candidate.content_hash);
+        assert_eq!(
+            record.recipient_lock_hash().unwrap(),
+            candidate.recipient_lock_hash
+        );
+    }
+    assert_eq!(reader.version_opt().unwrap(), vote_meta.version);
+    assert_eq!(reader.proposer_bond_opt().unwrap(), vote_meta.proposer_bond);
+    assert_eq!(
+        reader.bond_forfeit_lock_hash_opt().unwrap(),
+        vote_meta.bond_forfeit_lock_hash
+    );
+    assert_eq!(
+        reader.closure_policy_opt().unwrap(),
+        vote_meta.closure_policy
+    );
+    let committee: Option<Vec<[u8; 32]>> = reader
+        .committee_opt()
+        .unwrap()
+        .map(|committee| committee.into_iter().collect());
+    assert_eq!(committee, vote_meta.committee);
+    assert_eq!(
+        reader.committee_threshold_opt().unwrap(),
+        vote_meta.committee_threshold
+    );
+    assert_eq!(
+        reader.eligibility_mode_opt().unwrap(),
+        vote_meta.eligibility_mode
+    );
+    assert_eq!(
+        reader.snapshot_block_hash_opt().unwrap(),
+        vote_meta.snapshot_block_hash
+    );
+    assert_eq!(
+        reader.deposit_type_hash_opt().unwrap(),
+        vote_meta.deposit_type_hash
+    );
+    assert_eq!(
+        reader.escrow_lock_code_hash_opt().unwrap(),
+        vote_meta.escrow_lock_code_hash
+    );
+    assert_eq!(
+        reader.escrow_min_epochs_opt().unwrap(),
+        vote_meta.escrow_min_epochs
+    );
+    assert_eq!(
+        reader.escrow_max_epochs_opt().unwrap(),
+        vote_meta.escrow_max_epochs
+    );
+    assert_eq!(
+        reader.escrow_lock_hash_type_opt().unwrap(),
+        vote_meta.escrow_lock_hash_type
+    );
+
+    // all optional fields unset
+    let vote_meta = VoteMeta::default();
+    let reader = schema::VoteMeta::from(Cursor::from(to_vec(&vote_meta, false).unwrap()));
+    reader.verify(true).unwrap();
+    assert_eq!(reader.smt_root_hash().unwrap(), None);
+    assert!(reader.extra().unwrap().is_none());
+    assert!(reader.candidate_records_opt().unwrap().is_none());
+    assert!(reader.committee_opt().unwrap().is_none());
+    assert_eq!(reader.escrow_lock_hash_type_opt().unwrap(), None);
+
+    let legacy = LegacyVoteMeta {
+        smt_root_hash: None,
+        candidates: vec![vec![0]],
+        start_time: 0,
+        end_time: 0,
+        extra: None,
+    };
+    let reader = schema::VoteMeta::from(Cursor::from(to_vec(&legacy, false).unwrap()));
+    reader.verify_partial().unwrap();
+    assert_eq!(reader.min_vote_capacity_opt().unwrap(), None);
+    assert_eq!(reader.escrow_max_epochs_opt().unwrap(), None);
+    assert_eq!(reader.escrow_lock_hash_type_opt().unwrap(), None);
+
+    let proof = VoteProof {
+        lock_script_hash: [1; 32],
+        smt_proof: vec![2, 3],
+    };
+    let reader = schema::VoteProof::from(Cursor::from(to_vec(&proof, false).unwrap()));
+    reader.verify(false).unwrap();
+    assert_eq!(reader.lock_script_hash().unwrap(), proof.lock_script_hash);
+    assert_eq!(raw(reader.smt_proof().unwrap()), proof.smt_proof);
+
+    let tally = Tally {
+        session_id: [1; 20],
+        counts: vec![2, 3, 4],
+        turnout: 9,
+        abstain: 5,
+        voted_root: [6; 32],
+    };
+    let reader = schema::Tally::from(Cursor::from(to_vec(&tally, false).unwrap()));
+    reader.verify(false).unwrap();
+    assert_eq!(reader.session_id().unwrap(), tally.session_id);
+    let counts: Vec<u64> = reader.counts().unwrap().into_iter().collect();
+    assert_eq!(counts, tally.counts);
+    assert_eq!(reader.turnout().unwrap(), tally.turnout);
+    assert_eq!(reader.abstain().unwrap(), tally.abstain);
+    assert_eq!(reader.voted_root().unwrap(), tally.voted_root);
+
+    let result = VoteResult {
+        session_id: [1; 20],
+        meta_hash: [2; 32],
+        winners: 3,
+        counts: vec![4, 5],
+        turnout: 9,
+        abstain: 6,
+        quorum_reached: 7,
+        passed: 8,
+    };
+    let reader = schema::VoteResult::from(Cursor::from(to_vec(&result, false).unwrap()));
+    reader.verify(false).unwrap();
+    assert_eq!(reader.session_id().unwrap(), result.session_id);
+    assert_eq!(reader.meta_hash().unwrap(), result.meta_hash);
+    assert_eq!(reader.winners().unwrap(), result.winners);
+    let counts: Vec<u64> = reader.counts().unwrap().into_iter().collect();
+    assert_eq!(counts, result.counts);
+    assert_eq!(reader.turnout().unwrap(), result.turnout);
+    assert_eq!(reader.abstain().unwrap(), result.abstain);
+    assert_eq!(reader.quorum_reached().unwrap(), result.quorum_reached);
+    assert_eq!(reader.passed().unwrap(), result.passed);
+
+    let witness_args = WitnessArgs {
+        lock: Some(vec![1]),
+        input_type: None,
+        output_type: Some(vec![2, 3]),
+    };
+    let reader = schema::WitnessArgs::from(Cursor::from(to_vec(&witness_args, false).unwrap()));
+    reader.verify(false).unwrap();
+    assert_eq!(reader.lock().unwrap().map(raw), witness_args.lock);
+    assert_eq!(
+        reader.input_type().unwrap().map(raw),
+        witness_args.input_type
+    );
+    assert_eq!(
+        reader.output_type().unwrap().map(raw),
+        witness_args.output_type
+    );
+}
+
+#[test]
+fn test_sdk_vote_and_reclaim() {
+    use ckb_dao_vote_sdk::{ckb_types as sdk, BallotBuilder, ReclaimBuilder, VoteSessionBuilder};
+    use sdk::prelude::Entity as _;
+
+    let mut context = Context::default();
+    let out_point = context.deploy_cell_by_name("ckb-dao-vote");
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let always_success_script = context
+        .build_script(&always_success_out_point, Bytes::new())
+        .unwrap();
+    let vote_script = context.build_script(&out_point, Bytes::new()).unwrap();
+
+    // the SDK may use another ckb-types version, convert through molecule bytes
+    let voter_lock = sdk::packed::Script::from_slice(always_success_script.as_slice()).unwrap();
+    let hash_type: u8 = vote_script.hash_type().into();
+    let builder = VoteSessionBuilder::new(VoteMeta {
+        smt_root_hash: None,
+        candidates: vec![vec![0], vec![1]],
+        start_time: 0,
+        end_time: GUARDED_END_BLOCK,
+        extra: None,
+        guarded_consumption: Some(1),
+        ..Default::default()
+    })
+    .script(
+        sdk::packed::Byte32::from_slice(vote_script.code_hash().as_slice()).unwrap(),
+        sdk::core::ScriptHashType::try_from(hash_type).unwrap(),
+    );
+    let (meta_output, meta_data) = builder
+        .meta_output(voter_lock.clone(), sdk::core::Capacity::shannons(1000))
+        .unwrap();
+    let meta_out_point = context.create_cell(
+        CellOutput::from_slice(meta_output.as_slice()).unwrap(),
+        Bytes::from(meta_data.to_vec()),
+    );
+    let session = builder
+        .build(sdk::packed::OutPoint::from_slice(meta_out_point.as_slice()).unwrap())
+        .unwrap();
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(always_success_script.clone())
+            .build(),
+        Bytes::new(),
+    );
+    let tx = TransactionBuilder::default()
+        .input(
+            CellInput::new_builder()
+                .previous_output(input_out_point)
+                .build(),
+        )
+        .build();
+    let tx = sdk::packed::Transaction::from_slice(tx.data().as_slice())
+        .unwrap()
+        .into_view();
+    // indexes outside the bitmap or the candidates are rejected
+    for index in [2, 32, 64] {
+        let err = BallotBuilder::new(&session, voter_lock.clone())
+            .candidate(index)
+            .ballot()
+            .unwrap_err();
+        assert!(matches!(err, ckb_dao_vote_sdk::Error::WrongCandidate(i) if i == index));
+    }
+    // a version 2 ballot records the weight of the session's weight mode
+    let capacity_session = VoteSessionBuilder::new(VoteMeta {
+        candidates: vec![vec![0], vec![1]],
+        version: Some(2),
+        weight_mode: Some(1),
+        ..Default::default()
+    })
+    .script(
+        sdk::packed::Byte32::from_slice(vote_script.code_hash().as_slice()).unwrap(),
+        sdk::core::ScriptHashType::try_from(hash_type).unwrap(),
+    )
+    .build(session.meta_out_point().clone())
+    .unwrap();
+    let ballot = |weight: u64| {
+        BallotBuilder::new(&capacity_session, voter_lock.clone())
+            .candidate(0)
+            .capacity(500)
+            .weight(weight)
+            .ballot()
+    };
+    assert_eq!(
+        ballot(500).unwrap().to_vec(),
+        [&[1, 0, 0, 0][..], &500u64.to_le_bytes()].concat()
+    );
+    assert!(matches!(
+        ballot(1).unwrap_err(),
+        ckb_dao_vote_sdk::Error::WrongBallotWeight
+    ));
+    // the vote cell may be locked by another lock than the voter's
+    let vote_lock = context
+        .build_script(&always_success_out_point, Bytes::from(vec![9]))
+        .unwrap();
+    let tx = BallotBuilder::new(&session, voter_lock.clone())
+        .vote_lock(sdk::packed::Script::from_slice(vote_lock.as_slice()).unwrap())
+        .candidate(1)
+        .capacity(500)
+        .build(tx)
+        .unwrap();
+    let tx = Transaction::from_slice(tx.data().as_slice())
+        .unwrap()
+        .into_view();
+    assert_eq!(tx.outputs().get(0).unwrap().lock(), vote_lock);
+    // the vote proof still names the voter
+    let vote_proof = VoteProof {
+        lock_script_hash: always_success_script
+            .calc_script_hash()
+            .as_slice()
+            .try_into()
+            .unwrap(),
+        smt_proof: vec![],
+    };
+    let witness = ckb_testtool::ckb_types::packed::WitnessArgs::from_slice(
+        &tx.witnesses().get(0).unwrap().raw_data(),
+    )
+    .unwrap();
+    assert_eq!(
+        witness.output_type().to_opt().unwrap().raw_data(),
+        to_vec(&vote_proof, false).unwrap()
+    );
+    let tx = context.complete_tx(tx);
+    let cycles = context
+        .verify_tx(&tx, 10_000_000)
+        .expect("pass verification");
+    println!("vote cycles: {}", cycles);
+
+    let vote_output = tx.outputs().get(0).unwrap();
+    let vote_out_point = context.create_cell(
+        vote_output.clone(),
+        tx.outputs_data().get(0).unwrap().raw_data(),
+    );
+    let tx = TransactionBuilder::default()
+        .output(
+            CellOutput::new_builder()
+                .capacity(500u64.pack())
+                .lock(always_success_script.clone())
+                .build(),
+        )
+        .output_data(Bytes::new().pack())
+        .build();
+    let tx = sdk::packed::Transaction::from_slice(tx.data().as_slice())
+        .unwrap()
+        .into_view();
+    let tx = ReclaimBuilder::new(&session)
+        .vote_cell(sdk::packed::OutPoint::from_slice(vote_out_point.as_slice()).unwrap())
+        .build(tx);
+    let tx = Transaction::from_slice(tx.data().as_slice())
+        .unwrap()
+        .into_view();
+    let tx = context.complete_tx(tx);
+    let cycles = context
+        .verify_tx(&tx, 10_000_000)
+        .expect("pass verification");
+    println!("reclaim cycles: {}", cycles);
+
+    // consuming the vote meta cell ends the session, once `end_time` is reached
+    let vote_out_point = context.create_cell(vote_output, Bytes::from(vec![2, 0, 0, 0]));
+    let tx = TransactionBuilder::default()
+        .output(
+            CellOutput::new_builder()
+                .capacity(1500u64.pack())
+                .lock(always_success_script.clone())
+                .build(),
+        )
+        .output_data(Bytes::new().pack())
+        .build();
+    let tx = sdk::packed::Transaction::from_slice(tx.data().as_slice())
+        .unwrap()
+        .into_view();
+    let tx = ReclaimBuilder::new(&session)
+        .vote_cell(sdk::packed::OutPoint::from_slice(vote_out_point.as_slice()).unwrap())
+        .consume_meta_cell()
+        .build(tx);
+    let tx = Transaction::from_slice(tx.data().as_slice())
+        .unwrap()
+        .into_view();
+    let meta_input = tx.inputs().get(1).unwrap();
+    assert_eq!(meta_input.previous_output(), meta_out_point);
+    assert_eq!(
+        Unpack::<u64>::unpack(&meta_input.since()),
+        GUARDED_END_BLOCK
+    );
+    let tx = context.complete_tx(tx);
+    let cycles = context
+        .verify_tx(&tx, 10_000_000)
+        .expect("pass verification");
+    println!("reclaim with the vote meta cell cycles: {}", cycles);
+}
+
+#[test]
+fn test_eligibility_tree() {
+    use ckb_dao_vote_smt::{parse_csv, parse_json, EligibilityTree};
+    use sparse_merkle_tree::CompiledMerkleProof;
+
+    let mut context = Context::default();
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let mut smt_tree = CkbSMT::default();
+    let mut csv = String::new();
+    let mut json = Vec::new();
+    for i in 0..3u8 {
+        let lock = context
+            .build_script(&always_success_out_point, Bytes::from(vec![i]))
+            .unwrap();
+        let key: [u8; 32] = lock.calc_script_hash().as_slice().try_into().unwrap();
+        smt_tree.update(key.into(), SMT_VALUE.into()).unwrap();
+        if i == 0 {
+            csv.push_str(&format!("0x{},5\n", hex(&key)));
+        } else {
+            json.push(format!(
+                r#"{{"lock":{{"code_hash":"0x{}","hash_type":"{}","args":"0x{:02x}"}}}}"#,
+                hex(lock.code_hash().as_slice()),
+                match u8::from(lock.hash_type()) {
+                    0 => "data",
+                    1 => "type",
+                    2 => "data1",
+                    _ => "data2",
+                },
+                i
+            ));
+        }
+    }
+    let mut voters = parse_csv(&csv).unwrap();
+    voters.extend(parse_json(&format!("[{}]", json.join(","))).unwrap());
+    let tree = EligibilityTree::new(&voters).unwrap();
+    let root: [u8; 32] = smt_tree.root().clone().into();
+    assert_eq!(tree.root(), root);
+    assert_eq!(tree.eligible_weight(), 7);
+    for voter in &voters {
+        let proof = CompiledMerkleProof(tree.proof(&voter.lock_hash).unwrap());
+        assert!(proof
+            .verify::<Blake2bHasher>(
+                &root.into(),
+                vec![(voter.lock_hash.into(), SMT_VALUE.into())]
+            )
+            .unwrap());
+    }
+    assert!(EligibilityTree::new(&[voters[0].clone(), voters[0].clone()]).is_err());
+    assert!(tree.proof(&[0u8; 32]).is_err());
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[test]
+fn test_off_chain_tally() {
+    use ckb_dao_vote_sdk::{ckb_types as sdk, BallotBuilder, VoteSessionBuilder};
+    use ckb_dao_vote_tally::{load_block_transactions, DedupPolicy, TallyEngine};
+    use sdk::prelude::Entity as _;
+
+    let mut context = Context::default();
+    let out_point = context.deploy_cell_by_name("ckb-dao-vote");
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let always_success_script = context
+        .build_script(&always_success_out_point, Bytes::new())
+        .unwrap();
+    let vote_script = context.build_script(&out_point, Bytes::new()).unwrap();
+
+    let hash_type: u8 = vote_script.hash_type().into();
+    let code_hash = sdk::packed::Byte32::from_slice(vote_script.code_hash().as_slice()).unwrap();
+    let hash_type = sdk::core::ScriptHashType::try_from(hash_type).unwrap();
+    let builder = VoteSessionBuilder::new(VoteMeta {
+        smt_root_hash: None,
+        candidates: vec![vec![0], vec![1], vec![2]],
+        start_time: 0,
+        end_time: 0,
+        extra: None,
+        ..Default::default()
+    })
+    .script(code_hash.clone(), hash_type);
+    let meta_data = builder.meta_data().unwrap();
+    let meta_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(always_success_script.clone())
+            .build(),
+        Bytes::from(meta_data.to_vec()),
+    );
+    let session = builder
+        .build(sdk::packed::OutPoint::from_slice(meta_out_point.as_slice()).unwrap())
+        .unwrap();
+
+    // voter 0 changes its vote in block 3, voter 2 casts an invalid ballot
+    let mut transactions = Vec::new();
+    for (block_number, voter, choices) in
+        [(1u64, 0u8, 0b001), (2, 1, 0b010), (3, 0, 0b100), (4, 2, 0)]
+    {
+        let voter_lock = context
+            .build_script(&always_success_out_point, Bytes::from(vec![voter]))
+            .unwrap();
+        let input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(voter_lock.clone())
+                .build(),
+            Bytes::new(),
+        );
+        let tx = TransactionBuilder::default()
+            .input(
+                CellInput::new_builder()
+                    .previous_output(input_out_point)
+                    .build(),
+            )
+            .build();
+        let tx = sdk::packed::Transaction::from_slice(tx.data().as_slice())
+            .unwrap()
+            .into_view();
+        let tx = BallotBuilder::new(
+            &session,
+            sdk::packed::Script::from_slice(voter_lock.as_slice()).unwrap(),
+        )
+        .choices(choices.max(1))
+        .capacity(500)
+        .build(tx)
+        .unwrap();
+        let mut tx = Transaction::from_slice(tx.data().as_slice())
+            .unwrap()
+            .into_view();
+        if choices == 0 {
+            tx = tx
+                .as_advanced_builder()
+                .set_outputs_data(vec![Bytes::from(vec![0, 0, 0, 0]).pack()])
+                .build();
+        }
+        let tx = context.complete_tx(tx);
+        transactions.push(serde_json::json!({
+            "block_number": block_number,
+            "tx": context.dump_tx(&tx).unwrap(),
+        }));
+    }
+    let transactions =
+        load_block_transactions(&serde_json::to_string(&transactions).unwrap()).unwrap();
+    let session_id = session.session_id();
+    assert_eq!(
+        transactions[0]
+            .meta_cell_data(&session_id)
+            .unwrap()
+            .to_vec(),
+        meta_data.to_vec()
+    );
+    let cells: Vec<_> = transactions.iter().flat_map(|tx| tx.vote_cells()).collect();
+
+    let engine = TallyEngine::new(
+        &meta_data,
+        session_id,
+        code_hash.clone(),
+        hash_type,
+        DedupPolicy::LatestWins,
+    )
+    .unwrap();
+    let report = engine.tally(&cells);
+    assert_eq!(report.counts, vec![0, 1, 1]);
+    assert_eq!(report.turnout, 2);
+    assert_eq!(report.ballots, 2);
+    assert_eq!(report.superseded, 1);
+    assert_eq!(report.rejected.len(), 1);
+    assert_eq!(report.rejected[0].code, ErrorCode::AbstainNotAllowed.code());
+    assert_eq!(report.winners, 0b110);
+    assert!(!report.passed);
+
+    let engine = TallyEngine::new(
+        &meta_data,
+        session_id,
+        code_hash,
+        hash_type,
+        DedupPolicy::FirstWins,
+    )
+    .unwrap();
+    let report = engine.tally(&cells);
+    assert_eq!(report.counts, vec![1, 1, 0]);
+    assert_eq!(report.superseded, 1);
+
+    // the same args under another type script are not vote cells of the session
+    let engine = TallyEngine::new(
+        &meta_data,
+        session_id,
+        sdk::packed::Byte32::from_slice(&[3u8; 32]).unwrap(),
+        hash_type,
+        DedupPolicy::LatestWins,
+    )
+    .unwrap();
+    let report = engine.tally(&cells);
+    assert_eq!(report.ballots, 0);
+    assert!(report.rejected.is_empty());
+}
+
+#[test]
+fn test_local_indexer() {
+    use ckb_dao_vote_indexer::{http::route, Indexer, Phase};
+    use ckb_dao_vote_sdk::{ckb_types as sdk, BallotBuilder, VoteSessionBuilder};
+    use sdk::prelude::Entity as _;
+
+    let to_sdk = |tx: &ckb_testtool::ckb_types::core::TransactionView| {
+        sdk::packed::Transaction::from_slice(tx.data().as_slice())
+            .unwrap()
+            .into_view()
+    };
+    let to_json = |block_number: u64, tx: &sdk::core::TransactionView| {
+        serde_json::json!({
+            "block_number": block_number,
+            "transaction": ckb_jsonrpc_types::Transaction::from(tx.data()),
+        })
+        .to_string()
+    };
+    let code_hash = [1u8; 32];
+    let always_success_script = Script::new_builder().code_hash([2u8; 32].pack()).build();
+
+    // the window is open from block 2 until block 10
+    let builder = VoteSessionBuilder::new(VoteMeta {
+        smt_root_hash: None,
+        candidates: vec![vec![0], vec![1]],
+        start_time: 2,
+        end_time: 10,
+        extra: None,
+        ..Default::default()
+    })
+    .script(
+        sdk::packed::Byte32::from_slice(&code_hash).unwrap(),
+        sdk::core::ScriptHashType::Type,
+    );
+    let meta_tx = to_sdk(
+        &TransactionBuilder::default()
+            .output(
+                CellOutput::new_builder()
+                    .capacity(1000u64.pack())
+                    .lock(always_success_script.clone())
+                    .build(),
+            )
+            .output_data(Bytes::from(builder.meta_data().unwrap().to_vec()).pack())
+            .build(),
+    );
+    let meta_out_point = sdk::packed::OutPoint::new(meta_tx.hash(), 0);
+    let session = builder.build(meta_out_point.clone()).unwrap();
+    let session_id = session.session_id();
+
+    let mut indexer = Indexer::new(
+        sdk::packed::Byte32::from_slice(&code_hash).unwrap(),
+        sdk::core::ScriptHashType::Type,
+    );
+    assert!(indexer.session_status(&session_id).is_none());
+    indexer.ingest_json(&to_json(1, &meta_tx)).unwrap();
+    let status = indexer.session_status(&session_id).unwrap();
+    assert_eq!(status.phase, Phase::Pending);
+    assert_eq!(status.ballots, 0);
+    // a session without ballots is only found by its vote meta cell
+    assert_eq!(indexer.sessions().count(), 0);
+    assert_eq!(indexer.live_sessions(), vec![session_id]);
+
+    // voter 0 votes twice, voter 1 reclaims its vote cell in block 6
+    let mut vote_out_points = Vec::new();
+    for (block_number, voter) in [(3u64, 0u8), (4, 1), (5, 0)] {
+        let voter_lock = always_success_script
+            .as_builder()
+            .args(Bytes::from(vec![voter]).pack())
+            .build();
+        let tx = BallotBuilder::new(
+            &session,
+            sdk::packed::Script::from_slice(voter_lock.as_slice()).unwrap(),
+        )
+        .choices(1 << voter)
+        .capacity(500)
+        .build(to_sdk(&TransactionBuilder::default().build()))
+        .unwrap();
+        vote_out_points.push(sdk::packed::OutPoint::new(tx.hash(), 0));
+        indexer.ingest_json(&to_json(block_number, &tx)).unwrap();
+    }
+    let reclaim_tx = to_sdk(
+        &TransactionBuilder::default()
+            .input(
+                CellInput::new_builder()
+                    .previous_output(OutPoint::from_slice(vote_out_points[1].as_slice()).unwrap())
+                    .build(),
+            )
+            .build(),
+    );
+    indexer.ingest_json(&to_json(6, &reclaim_tx)).unwrap();
+
+    let ballots = indexer.ballots(&session_id);
+    assert_eq!(ballots.len(), 3);
+    assert_eq!(ballots[2].choices(), Some(1));
+    assert_eq!(ballots[1].consumed_at, Some(6));
+    let voter_hash = |voter: u8| -> [u8; 32] {
+        always_success_script
+            .as_builder()
+            .args(Bytes::from(vec![voter]).pack())
+            .build()
+            .calc_script_hash()
+            .as_slice()
+            .try_into()
+            .unwrap()
+    };
+    assert!(indexer.has_voted(&session_id, &voter_hash(0)));
+    assert!(indexer.has_voted(&session_id, &voter_hash(1)));
+    assert!(!indexer.has_voted(&session_id, &voter_hash(2)));
+    let status = indexer.session_status(&session_id).unwrap();
+    assert_eq!(status.phase, Phase::Open);
+    assert_eq!(status.ballots, 3);
+    assert_eq!(status.live_ballots, 2);
+    assert_eq!(status.voters, 2);
+
+    let session_path = format!("/sessions/0x{}", hex(&session_id));
+    let (code, body) = route(&indexer, &format!("{}/status", session_path));
+    assert_eq!(code, 200);
+    assert_eq!(body["phase"], "open");
+    let (_, body) = route(
+        &indexer,
+        &format!("{}/voters/0x{}", session_path, hex(&voter_hash(2))),
+    );
+    assert_eq!(body["has_voted"], false);
+    let (_, body) = route(&indexer, &format!("{}/ballots", session_path));
+    assert_eq!(body.as_array().unwrap().len(), 3);
+    assert_eq!(route(&indexer, "/sessions/0x00").0, 400);
+    let (_, body) = route(&indexer, "/sessions/live");
+    assert_eq!(body, serde_json::json!([format!("0x{}", hex(&session_id))]));
+
+    indexer
+        .ingest_json(&to_json(
+            10,
+            &to_sdk(&TransactionBuilder::default().build()),
+        ))
+        .unwrap();
+    assert_eq!(
+        indexer.session_status(&session_id).unwrap().phase,
+        Phase::Ended
+    );
+    let close_tx = to_sdk(
+        &TransactionBuilder::default()
+            .input(
+                CellInput::new_builder()
+                    .previous_output(OutPoint::from_slice(meta_out_point.as_slice()).unwrap())
+                    .build(),
+            )
+            .build(),
+    );
+    indexer.ingest_json(&to_json(11, &close_tx)).unwrap();
+    assert_eq!(
+        indexer.session_status(&session_id).unwrap().phase,
+        Phase::Closed
+    );
+    assert!(indexer.live_sessions().is_empty());
+}
+
+#[test]
+fn test_missing_witness() {
     entry(&Config {
         voter_count: 1,
         candidate_count: 5,
-        test_scheme: TestScheme::WrongVoteCandidateExceedLimit,
+        test_scheme: TestScheme::MissingWitness,
+    });
+}
+
+#[test]
+fn test_corrupt_witness() {
+    entry(&Config {
+        voter_count: 1,
+        candidate_count: 5,
+        test_scheme: TestScheme::CorruptWitness,
     });
 }
+
+#[test]
+fn test_script_failure_decoder() {
+    use ckb_dao_vote_sdk::ScriptFailure;
+
+    let mut context = Context::default();
+    let out_point = context.deploy_cell_by_name("ckb-dao-vote");
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let always_success_script = context
+        .build_script(&always_success_out_point, Bytes::new())
+        .unwrap();
+    // vote cell at output 1, without a vote meta cell dep
+    let type_script = context
+        .build_script(&out_point, Bytes::from(vec![0u8; 20]))
+        .unwrap();
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(always_success_script.clone())
+            .build(),
+        Bytes::new(),
+    );
+    let output = CellOutput::new_builder()
+        .capacity(500u64.pack())
+        .lock(always_success_script.clone())
+        .build();
+    let tx = TransactionBuilder::default()
+        .input(
+            CellInput::new_builder()
+                .previous_output(input_out_point)
+                .build(),
+        )
+        .output(output.clone())
+        .output(output.as_builder().type_(Some(type_script).pack()).build())
+        .outputs_data(vec![Bytes::new(), Bytes::from(vec![1, 0, 0, 0])].pack())
+        .build();
+    let tx = context.complete_tx(tx);
+    let err = context.verify_tx(&tx, 10_000_000).unwrap_err();
+
+    let failure = ScriptFailure::parse(&err.to_string()).unwrap();
+    assert_eq!(failure.code, ErrorCode::NoMetaCell.code());
+    assert_eq!(failure.source.as_deref(), Some("Outputs[1].Type"));
+    assert_eq!(failure.error(), Some(ErrorCode::NoMetaCell));
+    assert_eq!(failure.reason(), ErrorCode::NoMetaCell.description());
+
+    let unknown = ScriptFailure::from_code(-1);
+    assert!(unknown.error().is_none());
+    assert_eq!(unknown.to_string(), "unknown exit code");
+    for code in ErrorCode::ALL {
+        assert_eq!(ScriptFailure::from_code(code.code()).error(), Some(*code));
+    }
+}